@@ -0,0 +1,71 @@
+//! Measures how often each text scorer recovers the key for short English plaintexts encrypted
+//! with single-byte XOR. Run with `cargo bench --bench text_scoring`.
+
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+use cryptopals::text::{ChiSquared, englishiness, ENGLISH_BIGRAMS, ENGLISH_QUADGRAMS, ENGLISH_TRIGRAMS, PrintableAscii, TextScorer};
+use cryptopals::xor::{break_single_byte_xor, single_byte_xor};
+
+const SAMPLE_LENGTHS: [usize; 6] = [4, 8, 12, 16, 24, 32];
+const TRIALS: usize = 2000;
+
+// Sentences that don't appear in the training corpus
+const HELD_OUT_TEXT: &str = include_str!("../src/corpus/held_out_english.txt");
+
+fn main() {
+    let scorers: Vec<(&str, Box<dyn TextScorer>)> = vec![
+        ("chi-squared", Box::new((ChiSquared::english(), PrintableAscii))),
+        ("bigrams", Box::new((&*ENGLISH_BIGRAMS, PrintableAscii))),
+        ("trigrams", Box::new((&*ENGLISH_TRIGRAMS, PrintableAscii))),
+        ("quadgrams", Box::new((&*ENGLISH_QUADGRAMS, PrintableAscii))),
+    ];
+
+    print!("{:>8} {:>12}", "length", "englishiness");
+
+    for (name, _) in &scorers {
+        print!(" {:>12}", name);
+    }
+
+    println!();
+
+    let text = HELD_OUT_TEXT.as_bytes();
+
+    for sample_length in SAMPLE_LENGTHS {
+        let mut rng = StdRng::seed_from_u64(sample_length as u64);
+
+        let samples: Vec<(u8, Vec<u8>)> = (0..TRIALS)
+            .map(|_| {
+                let start = rng.gen_range(0..text.len() - sample_length);
+                let key = rng.gen();
+
+                (key, single_byte_xor(&text[start..start + sample_length], key))
+            })
+            .collect();
+
+        let legacy_successes = samples.iter()
+            .filter(|(key, ciphertext)| {
+                let guess = (0..=u8::MAX)
+                    .max_by_key(|&guess| englishiness(&String::from_utf8_lossy(&single_byte_xor(ciphertext, guess))))
+                    .unwrap();
+
+                guess == *key
+            })
+            .count();
+
+        print!("{:>8} {:>11.1}%", sample_length, accuracy(legacy_successes));
+
+        for (_, scorer) in &scorers {
+            let successes = samples.iter()
+                .filter(|(key, ciphertext)| break_single_byte_xor(ciphertext, scorer.as_ref()).0 == *key)
+                .count();
+
+            print!(" {:>11.1}%", accuracy(successes));
+        }
+
+        println!();
+    }
+}
+
+fn accuracy(successes: usize) -> f64 {
+    successes as f64 * 100.0 / TRIALS as f64
+}
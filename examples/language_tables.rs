@@ -0,0 +1,31 @@
+use std::env;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+use cryptopals::text::{ChiSquared, NgramModel};
+
+/// Trains the bundled language tables in src/tables from a corpus, e.g.
+///
+///     cargo run --release --example language_tables -- german german.txt src/tables
+///
+/// writes src/tables/german_quadgrams.bin and src/tables/german_chi_squared.bin.
+///
+/// The bundled tables were trained on the messages of a Debian system's gettext catalogs (with
+/// format directives, command line options and other words that aren't prose removed), followed
+/// by the vim tutor in the same language.
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    let [name, corpus, directory] = &args[..] else {
+        panic!("Usage: language_tables <name> <corpus> <output directory>");
+    };
+
+    let corpus = std::fs::read(corpus).unwrap();
+    let directory = Path::new(directory);
+
+    let quadgrams = NgramModel::train(4, &corpus);
+    quadgrams.write_to(&mut BufWriter::new(File::create(directory.join(format!("{}_quadgrams.bin", name))).unwrap())).unwrap();
+
+    let chi_squared = ChiSquared::train(&corpus);
+    chi_squared.write_to(&mut BufWriter::new(File::create(directory.join(format!("{}_chi_squared.bin", name))).unwrap())).unwrap();
+}
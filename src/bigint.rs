@@ -0,0 +1,1392 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, AddAssign, Div, Mul, MulAssign, Neg, Rem, Shl, Shr, Sub, SubAssign};
+use std::str::FromStr;
+use rand::Rng;
+
+// The largest power of ten that fits in a limb, for converting to and from decimal
+const DECIMAL_CHUNK: u64 = 10_000_000_000_000_000_000;
+const DECIMAL_CHUNK_DIGITS: usize = 19;
+
+/// An arbitrary-precision unsigned integer, stored as little-endian 64-bit limbs without any
+/// leading zero limbs (so zero has no limbs at all).
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct BigUint {
+    limbs: Vec<u64>,
+}
+
+/// An arbitrary-precision signed integer. Zero is never negative.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct BigInt {
+    negative: bool,
+    magnitude: BigUint,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct ParseBigIntError;
+
+impl BigUint {
+    pub fn zero() -> Self {
+        Self { limbs: vec![] }
+    }
+
+    pub fn one() -> Self {
+        Self { limbs: vec![1] }
+    }
+
+    fn from_limbs(mut limbs: Vec<u64>) -> Self {
+        while limbs.last() == Some(&0) {
+            limbs.pop();
+        }
+
+        Self { limbs }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    pub fn is_one(&self) -> bool {
+        self.limbs == [1]
+    }
+
+    /// Returns the number of significant bits (zero for zero).
+    pub fn bits(&self) -> u64 {
+        match self.limbs.last() {
+            Some(top) => self.limbs.len() as u64 * 64 - top.leading_zeros() as u64,
+            None => 0,
+        }
+    }
+
+    pub fn to_u64(&self) -> Option<u64> {
+        match self.limbs.as_slice() {
+            [] => Some(0),
+            [a] => Some(*a),
+            _ => None,
+        }
+    }
+
+    pub fn to_u128(&self) -> Option<u128> {
+        match self.limbs.as_slice() {
+            [] => Some(0),
+            [a] => Some(*a as u128),
+            [a, b] => Some(*a as u128 | (*b as u128) << 64),
+            _ => None,
+        }
+    }
+
+    /// Returns the nearest f64, or infinity if the value is too large to represent.
+    pub fn to_f64(&self) -> f64 {
+        let bits = self.bits();
+
+        if bits <= 64 {
+            return self.to_u64().unwrap() as f64;
+        }
+
+        // Keep the top 64 bits and fold everything below them into a sticky bit, which is enough
+        // for the conversion from u64 to round correctly
+        let shift = bits - 64;
+        let top = (self >> shift as usize).to_u64().unwrap();
+        let sticky = (&BigUint::from(top) << shift as usize != *self) as u64;
+
+        (top | sticky) as f64 * 2f64.powi(shift.min(i32::MAX as u64) as i32)
+    }
+
+    pub fn pow(&self, mut exponent: u32) -> Self {
+        let mut base = self.clone();
+        let mut result = Self::one();
+
+        while exponent > 0 {
+            if exponent & 1 == 1 {
+                result *= &base;
+            }
+
+            exponent >>= 1;
+
+            if exponent > 0 {
+                base = &base * &base;
+            }
+        }
+
+        result
+    }
+
+    /// Returns the quotient and remainder, using Knuth's algorithm D (TAOCP 4.3.1).
+    pub fn div_rem(&self, divisor: &Self) -> (Self, Self) {
+        assert!(!divisor.is_zero(), "Division by zero");
+
+        if self < divisor {
+            return (Self::zero(), self.clone());
+        }
+
+        if let [divisor] = divisor.limbs.as_slice() {
+            let (quotient, remainder) = self.div_rem_u64(*divisor);
+            return (quotient, Self::from(remainder));
+        }
+
+        // Normalize so that the divisor's top limb has its top bit set, which keeps each quotient
+        // limb estimate within two of the truth
+        let shift = divisor.limbs.last().unwrap().leading_zeros() as usize;
+        let v = (divisor << shift).limbs;
+        let mut u = (self << shift).limbs;
+        u.push(0);
+
+        let n = v.len();
+        let m = u.len() - n - 1;
+        let mut quotient = vec![0; m + 1];
+
+        for j in (0..=m).rev() {
+            let numerator = (u[j + n] as u128) << 64 | u[j + n - 1] as u128;
+            let mut q = numerator / v[n - 1] as u128;
+            let mut r = numerator % v[n - 1] as u128;
+
+            while q >> 64 != 0 || q * v[n - 2] as u128 > (r << 64 | u[j + n - 2] as u128) {
+                q -= 1;
+                r += v[n - 1] as u128;
+
+                if r >> 64 != 0 {
+                    break;
+                }
+            }
+
+            // Subtract q * v from the current window of u
+            let mut borrow = 0;
+            let mut carry = 0;
+
+            for i in 0..n {
+                let product = q * v[i] as u128 + carry;
+                carry = product >> 64;
+
+                let difference = u[i + j] as i128 - borrow - (product as u64) as i128;
+                u[i + j] = difference as u64;
+                borrow = (difference < 0) as i128;
+            }
+
+            let difference = u[j + n] as i128 - borrow - carry as i128;
+            u[j + n] = difference as u64;
+
+            // The estimate was still one too large (rare), so add v back
+            if difference < 0 {
+                q -= 1;
+
+                let mut carry = 0;
+
+                for i in 0..n {
+                    let sum = u[i + j] as u128 + v[i] as u128 + carry;
+                    u[i + j] = sum as u64;
+                    carry = sum >> 64;
+                }
+
+                u[j + n] = u[j + n].wrapping_add(carry as u64);
+            }
+
+            quotient[j] = q as u64;
+        }
+
+        u.truncate(n);
+
+        (Self::from_limbs(quotient), &Self::from_limbs(u) >> shift)
+    }
+
+    fn div_rem_u64(&self, divisor: u64) -> (Self, u64) {
+        assert_ne!(0, divisor, "Division by zero");
+
+        let mut quotient = vec![0; self.limbs.len()];
+        let mut remainder = 0u128;
+
+        for (i, &limb) in self.limbs.iter().enumerate().rev() {
+            let numerator = remainder << 64 | limb as u128;
+            quotient[i] = (numerator / divisor as u128) as u64;
+            remainder = numerator % divisor as u128;
+        }
+
+        (Self::from_limbs(quotient), remainder as u64)
+    }
+
+    /// Returns the remainder after dividing by a single limb, without building the quotient.
+    pub fn rem_u64(&self, divisor: u64) -> u64 {
+        assert_ne!(0, divisor, "Division by zero");
+
+        self.limbs.iter()
+            .rev()
+            .fold(0, |remainder, &limb| (((remainder as u128) << 64 | limb as u128) % divisor as u128) as u64)
+    }
+
+    /// Returns a uniformly random number below 2^bits.
+    pub fn random_bits(rng: &mut impl Rng, bits: u64) -> Self {
+        let mut limbs: Vec<u64> = (0..bits.div_ceil(64)).map(|_| rng.gen()).collect();
+
+        if !bits.is_multiple_of(64) {
+            *limbs.last_mut().unwrap() >>= 64 - bits % 64;
+        }
+
+        Self::from_limbs(limbs)
+    }
+
+    /// Returns a uniformly random number below `bound`.
+    pub fn random_below(rng: &mut impl Rng, bound: &Self) -> Self {
+        assert!(!bound.is_zero());
+
+        loop {
+            let candidate = Self::random_bits(rng, bound.bits());
+
+            if &candidate < bound {
+                return candidate;
+            }
+        }
+    }
+
+    pub fn gcd(&self, other: &Self) -> Self {
+        let mut a = self.clone();
+        let mut b = other.clone();
+
+        while !b.is_zero() {
+            let remainder = &a % &b;
+            a = b;
+            b = remainder;
+        }
+
+        a
+    }
+
+    pub fn is_even(&self) -> bool {
+        self.limbs.first().is_none_or(|limb| limb & 1 == 0)
+    }
+
+    pub fn is_odd(&self) -> bool {
+        !self.is_even()
+    }
+
+    /// Returns bit `i`, counting from the least significant bit.
+    pub fn bit(&self, i: u64) -> bool {
+        self.limbs
+            .get((i / 64) as usize)
+            .is_some_and(|limb| limb >> (i % 64) & 1 == 1)
+    }
+
+    pub fn from_bytes_be(bytes: &[u8]) -> Self {
+        Self::from_limbs(
+            bytes.rchunks(8)
+                .map(|chunk| chunk.iter().fold(0, |limb, &b| limb << 8 | b as u64))
+                .collect()
+        )
+    }
+
+    pub fn from_bytes_le(bytes: &[u8]) -> Self {
+        Self::from_limbs(
+            bytes.chunks(8)
+                .map(|chunk| chunk.iter().rev().fold(0, |limb, &b| limb << 8 | b as u64))
+                .collect()
+        )
+    }
+
+    /// Returns the big-endian bytes without leading zeros (zero is a single zero byte).
+    pub fn to_bytes_be(&self) -> Vec<u8> {
+        let mut bytes = self.to_bytes_le();
+        bytes.reverse();
+        bytes
+    }
+
+    /// Returns the little-endian bytes without trailing zeros (zero is a single zero byte).
+    pub fn to_bytes_le(&self) -> Vec<u8> {
+        let mut bytes: Vec<u8> = self.limbs.iter().flat_map(|limb| limb.to_le_bytes()).collect();
+
+        while bytes.len() > 1 && bytes.last() == Some(&0) {
+            bytes.pop();
+        }
+
+        if bytes.is_empty() {
+            bytes.push(0);
+        }
+
+        bytes
+    }
+
+    /// Parses hex digits in either case. Whitespace is ignored, so long constants can be split
+    /// across lines.
+    pub fn from_hex(hex: &str) -> Result<Self, ParseBigIntError> {
+        let digits: Vec<u8> = hex.bytes().filter(|b| !b.is_ascii_whitespace()).collect();
+
+        if digits.is_empty() || !digits.iter().all(u8::is_ascii_hexdigit) {
+            return Err(ParseBigIntError);
+        }
+
+        Ok(Self::from_limbs(
+            digits.rchunks(16)
+                .map(|chunk| u64::from_str_radix(std::str::from_utf8(chunk).unwrap(), 16).unwrap())
+                .collect()
+        ))
+    }
+
+    /// Returns `self^exponent mod modulus`. Odd moduli (which covers every modulus that matters
+    /// in practice) use Montgomery multiplication.
+    pub fn modpow(&self, exponent: &Self, modulus: &Self) -> Self {
+        assert!(!modulus.is_zero(), "Modulus must not be zero");
+
+        if modulus.is_odd() {
+            return Montgomery::new(modulus).pow(self, exponent);
+        }
+
+        let mut result = Self::one() % modulus;
+        let base = self % modulus;
+
+        for i in (0..exponent.bits()).rev() {
+            result = &result * &result % modulus;
+
+            if exponent.bit(i) {
+                result = &result * &base % modulus;
+            }
+        }
+
+        result
+    }
+
+    /// Returns the inverse of `self` modulo `modulus`, if there is one.
+    pub fn modinv(&self, modulus: &Self) -> Option<Self> {
+        let (gcd, x, _) = BigInt::from(self % modulus).extended_gcd(&BigInt::from(modulus.clone()));
+
+        (gcd.is_one()).then(|| x.rem_euclid(modulus))
+    }
+
+    /// Returns the largest integer whose `n`th power is at most `self`, using Newton's method.
+    pub fn nth_root(&self, n: u32) -> Self {
+        assert!(n > 0);
+
+        if self.is_zero() || n == 1 {
+            return self.clone();
+        }
+
+        let n_minus_one = BigUint::from(n - 1);
+        let n_big = BigUint::from(n);
+
+        // Start above the root, since the iteration only converges from above
+        let mut root = BigUint::one() << self.bits().div_ceil(n as u64) as usize;
+
+        loop {
+            let next = (&n_minus_one * &root + self / root.pow(n - 1)) / &n_big;
+
+            if next >= root {
+                return root;
+            }
+
+            root = next;
+        }
+    }
+
+    /// Returns the `n`th root of `self` if it's a perfect `n`th power.
+    pub fn exact_nth_root(&self, n: u32) -> Option<Self> {
+        let root = self.nth_root(n);
+
+        (&root.pow(n) == self).then_some(root)
+    }
+}
+
+/// Precomputed values for Montgomery multiplication modulo an odd number m, which replaces
+/// division by m with cheap operations on whole limbs. Numbers in Montgomery form are stored as
+/// x * R mod m, where R = 2^(64 * limbs in m).
+#[derive(Clone, Debug)]
+pub struct Montgomery {
+    modulus: BigUint,
+
+    // -m^-1 mod 2^64
+    m_prime: u64,
+
+    // R^2 mod m, for converting into Montgomery form
+    r_squared: BigUint,
+}
+
+impl Montgomery {
+    pub fn new(modulus: &BigUint) -> Self {
+        assert!(modulus.is_odd(), "Montgomery multiplication needs an odd modulus");
+
+        // Newton's iteration doubles the number of correct low bits each time
+        let m0 = modulus.limbs[0];
+        let mut inverse: u64 = 1;
+
+        for _ in 0..6 {
+            inverse = inverse.wrapping_mul(2u64.wrapping_sub(m0.wrapping_mul(inverse)));
+        }
+
+        Self {
+            modulus: modulus.clone(),
+            m_prime: inverse.wrapping_neg(),
+            r_squared: &(BigUint::one() << (128 * modulus.limbs.len())) % modulus,
+        }
+    }
+
+    pub fn modulus(&self) -> &BigUint {
+        &self.modulus
+    }
+
+    pub fn to_montgomery(&self, x: &BigUint) -> BigUint {
+        self.multiply(&(x % &self.modulus), &self.r_squared)
+    }
+
+    pub fn from_montgomery(&self, x: &BigUint) -> BigUint {
+        // One isn't less than a modulus of one
+        self.multiply(x, &(BigUint::one() % &self.modulus))
+    }
+
+    /// Returns a * b / R mod m for a and b less than m, using the CIOS method (coarsely
+    /// integrated operand scanning) from Koç, Acar and Kaliski.
+    pub fn multiply(&self, a: &BigUint, b: &BigUint) -> BigUint {
+        // Larger operands would have their high limbs silently dropped below
+        debug_assert!(a < &self.modulus && b < &self.modulus, "Montgomery operands must be less than the modulus");
+
+        let m = &self.modulus.limbs;
+        let n = m.len();
+        let mut t = vec![0u64; n + 2];
+
+        let mut b_limbs = b.limbs.clone();
+        b_limbs.resize(n, 0);
+
+        for i in 0..n {
+            let a_i = a.limbs.get(i).copied().unwrap_or(0) as u128;
+            let mut carry = 0;
+
+            for (t_j, &b_j) in t.iter_mut().zip(b_limbs.iter()) {
+                let sum = *t_j as u128 + a_i * b_j as u128 + carry;
+                *t_j = sum as u64;
+                carry = sum >> 64;
+            }
+
+            let sum = t[n] as u128 + carry;
+            t[n] = sum as u64;
+            t[n + 1] = (sum >> 64) as u64;
+
+            // Add a multiple of m that zeroes the lowest limb, then shift that limb away
+            let u = t[0].wrapping_mul(self.m_prime) as u128;
+            let mut carry = (t[0] as u128 + u * m[0] as u128) >> 64;
+
+            for j in 1..n {
+                let sum = t[j] as u128 + u * m[j] as u128 + carry;
+                t[j - 1] = sum as u64;
+                carry = sum >> 64;
+            }
+
+            let sum = t[n] as u128 + carry;
+            t[n - 1] = sum as u64;
+            t[n] = t[n + 1] + (sum >> 64) as u64;
+        }
+
+        let result = BigUint::from_limbs(t[..=n].to_vec());
+
+        if result >= self.modulus {
+            result - &self.modulus
+        } else {
+            result
+        }
+    }
+
+    /// Returns base^exponent mod m (in normal form), four exponent bits at a time.
+    pub fn pow(&self, base: &BigUint, exponent: &BigUint) -> BigUint {
+        const WINDOW_BITS: u64 = 4;
+
+        let base = self.to_montgomery(base);
+        let mut powers = vec![self.to_montgomery(&BigUint::one()), base.clone()];
+
+        for i in 2..1 << WINDOW_BITS {
+            powers.push(self.multiply(&powers[i - 1], &base));
+        }
+
+        let mut result = powers[0].clone();
+        let mut i = exponent.bits();
+
+        while i > 0 {
+            let window = WINDOW_BITS.min(i);
+
+            for _ in 0..window {
+                result = self.multiply(&result, &result);
+            }
+
+            let digit = (i - window..i).rev().fold(0, |digit, bit| digit << 1 | exponent.bit(bit) as usize);
+
+            if digit != 0 {
+                result = self.multiply(&result, &powers[digit]);
+            }
+
+            i -= window;
+        }
+
+        self.from_montgomery(&result)
+    }
+}
+
+impl BigInt {
+    pub fn zero() -> Self {
+        Self::default()
+    }
+
+    pub fn one() -> Self {
+        Self::from(1)
+    }
+
+    pub fn from_biguint(negative: bool, magnitude: BigUint) -> Self {
+        Self {
+            negative: negative && !magnitude.is_zero(),
+            magnitude,
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.magnitude.is_zero()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    pub fn is_positive(&self) -> bool {
+        !self.negative && !self.is_zero()
+    }
+
+    pub fn magnitude(&self) -> &BigUint {
+        &self.magnitude
+    }
+
+    pub fn into_magnitude(self) -> BigUint {
+        self.magnitude
+    }
+
+    pub fn abs(&self) -> Self {
+        Self::from_biguint(false, self.magnitude.clone())
+    }
+
+    /// Returns the value as a [BigUint], or `None` if it's negative.
+    pub fn to_biguint(&self) -> Option<BigUint> {
+        (!self.negative).then(|| self.magnitude.clone())
+    }
+
+    pub fn to_i64(&self) -> Option<i64> {
+        let magnitude = self.magnitude.to_u64()?;
+
+        if self.negative {
+            0i64.checked_sub_unsigned(magnitude)
+        } else {
+            i64::try_from(magnitude).ok()
+        }
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        let magnitude = self.magnitude.to_f64();
+
+        if self.negative {
+            -magnitude
+        } else {
+            magnitude
+        }
+    }
+
+    pub fn pow(&self, exponent: u32) -> Self {
+        Self::from_biguint(self.negative && exponent & 1 == 1, self.magnitude.pow(exponent))
+    }
+
+    /// Returns the quotient rounded toward zero and the remainder, which takes the sign of the
+    /// dividend (like Rust's primitive integers).
+    pub fn div_rem(&self, divisor: &Self) -> (Self, Self) {
+        let (quotient, remainder) = self.magnitude.div_rem(&divisor.magnitude);
+
+        (
+            Self::from_biguint(self.negative != divisor.negative, quotient),
+            Self::from_biguint(self.negative, remainder),
+        )
+    }
+
+    /// Returns the quotient rounded toward negative infinity and the remainder, which takes the
+    /// sign of the divisor.
+    pub fn div_rem_floor(&self, divisor: &Self) -> (Self, Self) {
+        let (quotient, remainder) = self.div_rem(divisor);
+
+        if !remainder.is_zero() && remainder.negative != divisor.negative {
+            (quotient - Self::one(), remainder + divisor)
+        } else {
+            (quotient, remainder)
+        }
+    }
+
+    pub fn gcd(&self, other: &Self) -> BigUint {
+        self.magnitude.gcd(&other.magnitude)
+    }
+
+    /// Returns (g, x, y) such that g = gcd(self, other) = self * x + other * y.
+    pub fn extended_gcd(&self, other: &Self) -> (BigUint, Self, Self) {
+        let (mut old_r, mut r) = (self.clone(), other.clone());
+        let (mut old_x, mut x) = (Self::one(), Self::zero());
+        let (mut old_y, mut y) = (Self::zero(), Self::one());
+
+        while !r.is_zero() {
+            let quotient = &old_r / &r;
+
+            (old_r, r) = (r.clone(), &old_r - &quotient * &r);
+            (old_x, x) = (x.clone(), &old_x - &quotient * &x);
+            (old_y, y) = (y.clone(), &old_y - &quotient * &y);
+        }
+
+        if old_r.is_negative() {
+            (old_r.into_magnitude(), -old_x, -old_y)
+        } else {
+            (old_r.into_magnitude(), old_x, old_y)
+        }
+    }
+
+    /// Returns the least non-negative residue modulo `modulus`.
+    pub fn rem_euclid(&self, modulus: &BigUint) -> BigUint {
+        let remainder = &self.magnitude % modulus;
+
+        if self.negative && !remainder.is_zero() {
+            modulus - remainder
+        } else {
+            remainder
+        }
+    }
+}
+
+/// Solves a system of congruences x = a_i mod m_i with the Chinese Remainder Theorem, returning x
+/// and the product of the moduli (x being unique below it). Returns `None` if the moduli aren't
+/// pairwise coprime.
+pub fn chinese_remainder(congruences: &[(BigUint, BigUint)]) -> Option<(BigUint, BigUint)> {
+    congruences.iter().try_fold((BigUint::zero(), BigUint::one()), |(x, product), (residue, modulus)| {
+        // Find the y = x mod product that is also = residue mod modulus
+        let inverse = product.modinv(modulus)?;
+        let residue = residue % modulus;
+        let difference = (residue + modulus - &x % modulus) % modulus;
+        let y = x + &product * (difference * inverse % modulus);
+
+        Some((y, product * modulus))
+    })
+}
+
+impl Ord for BigUint {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.limbs.len()
+            .cmp(&other.limbs.len())
+            .then_with(|| self.limbs.iter().rev().cmp(other.limbs.iter().rev()))
+    }
+}
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, false) => self.magnitude.cmp(&other.magnitude),
+            (true, true) => other.magnitude.cmp(&self.magnitude),
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+        }
+    }
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Add<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn add(self, other: &BigUint) -> BigUint {
+        let (longer, shorter) = if self.limbs.len() >= other.limbs.len() {
+            (&self.limbs, &other.limbs)
+        } else {
+            (&other.limbs, &self.limbs)
+        };
+
+        let mut limbs = Vec::with_capacity(longer.len() + 1);
+        let mut carry = false;
+
+        for (i, &a) in longer.iter().enumerate() {
+            let (sum, overflow_a) = a.overflowing_add(shorter.get(i).copied().unwrap_or(0));
+            let (sum, overflow_b) = sum.overflowing_add(carry as u64);
+
+            limbs.push(sum);
+            carry = overflow_a || overflow_b;
+        }
+
+        limbs.push(carry as u64);
+
+        BigUint::from_limbs(limbs)
+    }
+}
+
+impl Sub<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn sub(self, other: &BigUint) -> BigUint {
+        assert!(self >= other, "attempt to subtract with overflow");
+
+        let mut limbs = Vec::with_capacity(self.limbs.len());
+        let mut borrow = false;
+
+        for (i, &a) in self.limbs.iter().enumerate() {
+            let (difference, overflow_a) = a.overflowing_sub(other.limbs.get(i).copied().unwrap_or(0));
+            let (difference, overflow_b) = difference.overflowing_sub(borrow as u64);
+
+            limbs.push(difference);
+            borrow = overflow_a || overflow_b;
+        }
+
+        BigUint::from_limbs(limbs)
+    }
+}
+
+impl Mul<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn mul(self, other: &BigUint) -> BigUint {
+        if self.is_zero() || other.is_zero() {
+            return BigUint::zero();
+        }
+
+        let mut limbs = vec![0; self.limbs.len() + other.limbs.len()];
+
+        for (i, &a) in self.limbs.iter().enumerate() {
+            let mut carry = 0;
+
+            for (j, &b) in other.limbs.iter().enumerate() {
+                let product = a as u128 * b as u128 + limbs[i + j] as u128 + carry;
+
+                limbs[i + j] = product as u64;
+                carry = product >> 64;
+            }
+
+            limbs[i + other.limbs.len()] = carry as u64;
+        }
+
+        BigUint::from_limbs(limbs)
+    }
+}
+
+impl Div<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn div(self, other: &BigUint) -> BigUint {
+        self.div_rem(other).0
+    }
+}
+
+impl Rem<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn rem(self, other: &BigUint) -> BigUint {
+        self.div_rem(other).1
+    }
+}
+
+impl Shl<usize> for &BigUint {
+    type Output = BigUint;
+
+    fn shl(self, shift: usize) -> BigUint {
+        if self.is_zero() {
+            return BigUint::zero();
+        }
+
+        let (limb_shift, bit_shift) = (shift / 64, shift % 64);
+        let mut limbs = vec![0; limb_shift];
+
+        if bit_shift == 0 {
+            limbs.extend_from_slice(&self.limbs);
+        } else {
+            let mut carry = 0;
+
+            for &limb in &self.limbs {
+                limbs.push(limb << bit_shift | carry);
+                carry = limb >> (64 - bit_shift);
+            }
+
+            limbs.push(carry);
+        }
+
+        BigUint::from_limbs(limbs)
+    }
+}
+
+impl Shr<usize> for &BigUint {
+    type Output = BigUint;
+
+    fn shr(self, shift: usize) -> BigUint {
+        let (limb_shift, bit_shift) = (shift / 64, shift % 64);
+
+        if limb_shift >= self.limbs.len() {
+            return BigUint::zero();
+        }
+
+        let limbs = &self.limbs[limb_shift..];
+
+        if bit_shift == 0 {
+            return BigUint::from_limbs(limbs.to_vec());
+        }
+
+        BigUint::from_limbs(
+            limbs.iter()
+                .enumerate()
+                .map(|(i, &limb)| limb >> bit_shift | limbs.get(i + 1).map_or(0, |next| next << (64 - bit_shift)))
+                .collect()
+        )
+    }
+}
+
+impl Neg for &BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> BigInt {
+        BigInt::from_biguint(!self.negative, self.magnitude.clone())
+    }
+}
+
+impl Neg for BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> BigInt {
+        BigInt::from_biguint(!self.negative, self.magnitude)
+    }
+}
+
+impl Add<&BigInt> for &BigInt {
+    type Output = BigInt;
+
+    fn add(self, other: &BigInt) -> BigInt {
+        if self.negative == other.negative {
+            return BigInt::from_biguint(self.negative, &self.magnitude + &other.magnitude);
+        }
+
+        match self.magnitude.cmp(&other.magnitude) {
+            Ordering::Less => BigInt::from_biguint(other.negative, &other.magnitude - &self.magnitude),
+            _ => BigInt::from_biguint(self.negative, &self.magnitude - &other.magnitude),
+        }
+    }
+}
+
+impl Sub<&BigInt> for &BigInt {
+    type Output = BigInt;
+
+    fn sub(self, other: &BigInt) -> BigInt {
+        self + &-other
+    }
+}
+
+impl Mul<&BigInt> for &BigInt {
+    type Output = BigInt;
+
+    fn mul(self, other: &BigInt) -> BigInt {
+        BigInt::from_biguint(self.negative != other.negative, &self.magnitude * &other.magnitude)
+    }
+}
+
+impl Div<&BigInt> for &BigInt {
+    type Output = BigInt;
+
+    fn div(self, other: &BigInt) -> BigInt {
+        self.div_rem(other).0
+    }
+}
+
+impl Rem<&BigInt> for &BigInt {
+    type Output = BigInt;
+
+    fn rem(self, other: &BigInt) -> BigInt {
+        self.div_rem(other).1
+    }
+}
+
+// Implements the owned variants of a binary operator in terms of the by-reference one
+macro_rules! forward_binary_operator {
+    ($type:ty, $operator:ident, $method:ident) => {
+        impl $operator<$type> for $type {
+            type Output = $type;
+
+            fn $method(self, other: $type) -> $type {
+                (&self).$method(&other)
+            }
+        }
+
+        impl $operator<&$type> for $type {
+            type Output = $type;
+
+            fn $method(self, other: &$type) -> $type {
+                (&self).$method(other)
+            }
+        }
+
+        impl $operator<$type> for &$type {
+            type Output = $type;
+
+            fn $method(self, other: $type) -> $type {
+                self.$method(&other)
+            }
+        }
+    };
+}
+
+macro_rules! forward_assign_operator {
+    ($type:ty, $operator:ident, $method:ident, $binary_method:ident) => {
+        impl $operator<&$type> for $type {
+            fn $method(&mut self, other: &$type) {
+                *self = (&*self).$binary_method(other);
+            }
+        }
+
+        impl $operator<$type> for $type {
+            fn $method(&mut self, other: $type) {
+                *self = (&*self).$binary_method(&other);
+            }
+        }
+    };
+}
+
+forward_binary_operator!(BigUint, Add, add);
+forward_binary_operator!(BigUint, Sub, sub);
+forward_binary_operator!(BigUint, Mul, mul);
+forward_binary_operator!(BigUint, Div, div);
+forward_binary_operator!(BigUint, Rem, rem);
+forward_assign_operator!(BigUint, AddAssign, add_assign, add);
+forward_assign_operator!(BigUint, SubAssign, sub_assign, sub);
+forward_assign_operator!(BigUint, MulAssign, mul_assign, mul);
+
+forward_binary_operator!(BigInt, Add, add);
+forward_binary_operator!(BigInt, Sub, sub);
+forward_binary_operator!(BigInt, Mul, mul);
+forward_binary_operator!(BigInt, Div, div);
+forward_binary_operator!(BigInt, Rem, rem);
+forward_assign_operator!(BigInt, AddAssign, add_assign, add);
+forward_assign_operator!(BigInt, SubAssign, sub_assign, sub);
+forward_assign_operator!(BigInt, MulAssign, mul_assign, mul);
+
+impl Shl<usize> for BigUint {
+    type Output = BigUint;
+
+    fn shl(self, shift: usize) -> BigUint {
+        &self << shift
+    }
+}
+
+impl Shr<usize> for BigUint {
+    type Output = BigUint;
+
+    fn shr(self, shift: usize) -> BigUint {
+        &self >> shift
+    }
+}
+
+macro_rules! from_unsigned {
+    ($($primitive:ty),*) => {
+        $(
+            impl From<$primitive> for BigUint {
+                fn from(value: $primitive) -> Self {
+                    let value = value as u128;
+                    Self::from_limbs(vec![value as u64, (value >> 64) as u64])
+                }
+            }
+
+            impl From<$primitive> for BigInt {
+                fn from(value: $primitive) -> Self {
+                    Self::from_biguint(false, BigUint::from(value))
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! from_signed {
+    ($($primitive:ty),*) => {
+        $(
+            impl From<$primitive> for BigInt {
+                fn from(value: $primitive) -> Self {
+                    Self::from_biguint(value < 0, BigUint::from(value.unsigned_abs()))
+                }
+            }
+        )*
+    };
+}
+
+from_unsigned!(u8, u16, u32, u64, u128, usize);
+from_signed!(i8, i16, i32, i64, i128, isize);
+
+impl From<BigUint> for BigInt {
+    fn from(magnitude: BigUint) -> Self {
+        Self::from_biguint(false, magnitude)
+    }
+}
+
+impl fmt::Display for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_zero() {
+            return f.pad_integral(true, "", "0");
+        }
+
+        let mut chunks = vec![];
+        let mut remaining = self.clone();
+
+        while !remaining.is_zero() {
+            let (quotient, chunk) = remaining.div_rem_u64(DECIMAL_CHUNK);
+            chunks.push(chunk);
+            remaining = quotient;
+        }
+
+        let mut digits = chunks.pop().unwrap().to_string();
+
+        for chunk in chunks.iter().rev() {
+            digits.push_str(&format!("{:0width$}", chunk, width = DECIMAL_CHUNK_DIGITS));
+        }
+
+        f.pad_integral(true, "", &digits)
+    }
+}
+
+impl fmt::Display for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad_integral(!self.negative, "", &self.magnitude.to_string())
+    }
+}
+
+impl fmt::LowerHex for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut digits = format!("{:x}", self.limbs.last().copied().unwrap_or(0));
+
+        for limb in self.limbs.iter().rev().skip(1) {
+            digits.push_str(&format!("{:016x}", limb));
+        }
+
+        f.pad_integral(true, "0x", &digits)
+    }
+}
+
+impl fmt::LowerHex for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad_integral(!self.negative, "0x", &format!("{:x}", self.magnitude))
+    }
+}
+
+impl FromStr for BigUint {
+    type Err = ParseBigIntError;
+
+    /// Parses a decimal number.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(ParseBigIntError);
+        }
+
+        let chunk_base = BigUint::from(DECIMAL_CHUNK);
+        let first_chunk_length = match s.len() % DECIMAL_CHUNK_DIGITS {
+            0 => DECIMAL_CHUNK_DIGITS,
+            length => length,
+        };
+
+        let mut value = BigUint::from(s[..first_chunk_length].parse::<u64>().unwrap());
+
+        for start in (first_chunk_length..s.len()).step_by(DECIMAL_CHUNK_DIGITS) {
+            let chunk = s[start..start + DECIMAL_CHUNK_DIGITS].parse::<u64>().unwrap();
+            value = &value * &chunk_base + BigUint::from(chunk);
+        }
+
+        Ok(value)
+    }
+}
+
+impl FromStr for BigInt {
+    type Err = ParseBigIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.strip_prefix('-') {
+            Some(magnitude) => Ok(Self::from_biguint(true, magnitude.parse()?)),
+            None => Ok(Self::from(s.parse::<BigUint>()?)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::Rng;
+    use crate::bigint::{chinese_remainder, BigInt, BigUint, Montgomery, ParseBigIntError};
+
+    #[test]
+    fn test_arithmetic() {
+        let a: BigUint = "340282366920938463463374607431768211457".parse().unwrap();
+        let b: BigUint = "18446744073709551617".parse().unwrap();
+
+        // (2^128 + 1) = (2^64 + 1) * (2^64 - 1) + 2
+        assert_eq!((BigUint::from(u64::MAX), BigUint::from(2u8)), a.div_rem(&b));
+        assert_eq!(a, &b * BigUint::from(u64::MAX) + BigUint::from(2u8));
+        assert_eq!(BigUint::from(u128::MAX), &a - BigUint::from(2u8));
+        assert_eq!("115792089237316195423570985008687907853269984665640564039457584007913129639936", (&a - BigUint::one()).pow(2).to_string());
+
+        let c: BigUint = "123456789012345678901234567890123456789012345678901234567890".parse().unwrap();
+        let d: BigUint = "987654321098765432109876543210".parse().unwrap();
+
+        assert_eq!("124999998860937500014238281249", (&c / &d).to_string());
+        assert_eq!("935329860093532986009353298600", (&c % &d).to_string());
+        assert_eq!(c, &c / &d * &d + &c % &d);
+
+        assert_eq!(BigUint::from(6u8), BigUint::from(84u8).gcd(&BigUint::from(90u8)));
+        assert_eq!(Err(ParseBigIntError), "12a".parse::<BigUint>());
+    }
+
+    #[test]
+    fn test_against_u128() {
+        let mut rng = rand::thread_rng();
+
+        for _ in 0..1_000 {
+            let a: u128 = rng.gen::<u128>() >> rng.gen_range(0..128);
+            let b: u128 = (rng.gen::<u128>() >> rng.gen_range(0..128)).max(1);
+            let shift = rng.gen_range(0..128);
+
+            let (big_a, big_b) = (BigUint::from(a), BigUint::from(b));
+
+            match a.checked_add(b) {
+                Some(sum) => assert_eq!(Some(sum), (&big_a + &big_b).to_u128()),
+                None => assert_eq!((BigUint::one() << 128) + BigUint::from(a.wrapping_add(b)), &big_a + &big_b),
+            }
+
+            assert_eq!(Some(a / b), (&big_a / &big_b).to_u128());
+            assert_eq!(Some(a % b), (&big_a % &big_b).to_u128());
+            assert_eq!(Some(a >> shift), (&big_a >> shift).to_u128());
+            assert_eq!(a.to_string(), big_a.to_string());
+            assert_eq!(a as f64, big_a.to_f64());
+
+            if let Some(product) = a.checked_mul(b) {
+                assert_eq!(Some(product), (&big_a * &big_b).to_u128());
+            }
+
+            if a >= b {
+                assert_eq!(Some(a - b), (&big_a - &big_b).to_u128());
+            }
+
+            let signed_a = a as i128 >> 1;
+            let signed_b = (b >> 1).max(1) as i128 * if rng.gen() { 1 } else { -1 };
+            let floor_quotient = signed_a / signed_b - (signed_a % signed_b != 0 && (signed_a < 0) != (signed_b < 0)) as i128;
+            let (big_signed_a, big_signed_b) = (BigInt::from(signed_a), BigInt::from(signed_b));
+
+            assert_eq!(BigInt::from(signed_a / signed_b), &big_signed_a / &big_signed_b);
+            assert_eq!(BigInt::from(signed_a % signed_b), &big_signed_a % &big_signed_b);
+            assert_eq!(BigInt::from(floor_quotient), big_signed_a.div_rem_floor(&big_signed_b).0);
+            assert_eq!(signed_a.cmp(&signed_b), big_signed_a.cmp(&big_signed_b));
+
+            // The operands range over most of i128, so sums and differences can overflow it
+            if let Some(sum) = signed_a.checked_add(signed_b) {
+                assert_eq!(BigInt::from(sum), &big_signed_a + &big_signed_b);
+            }
+
+            if let Some(difference) = signed_b.checked_sub(signed_a) {
+                assert_eq!(BigInt::from(difference), &big_signed_b - &big_signed_a);
+            }
+        }
+    }
+
+    #[test]
+    fn test_div_rem() {
+        let mut rng = rand::thread_rng();
+
+        // Limbs near 0 and u64::MAX make the quotient estimates wrong more often
+        let mut random = |limbs: usize| -> BigUint {
+            BigUint::from_limbs((0..limbs).map(|_| match rng.gen_range(0..3) {
+                0 => rng.gen_range(0..4),
+                1 => u64::MAX - rng.gen_range(0..4),
+                _ => rng.gen(),
+            }).collect())
+        };
+
+        for _ in 0..2_000 {
+            let a = random(8);
+            let b = random(1 + (a.limbs.len() * 3 / 4));
+
+            if b.is_zero() {
+                continue;
+            }
+
+            let (quotient, remainder) = a.div_rem(&b);
+
+            assert!(remainder < b);
+            assert_eq!(a, quotient * &b + remainder);
+        }
+    }
+
+    #[test]
+    fn test_signed() {
+        let a = BigInt::from(-7);
+        let b = BigInt::from(2);
+
+        assert_eq!((BigInt::from(-3), BigInt::from(-1)), a.div_rem(&b));
+        assert_eq!((BigInt::from(-4), BigInt::from(1)), a.div_rem_floor(&b));
+        assert_eq!(BigInt::from(-343), a.pow(3));
+        assert_eq!("-7", a.to_string());
+        assert_eq!(Ok(a.clone()), "-7".parse());
+        assert_eq!(Some(-7), a.to_i64());
+        assert_eq!(Some(i64::MIN), BigInt::from(i64::MIN).to_i64());
+        assert_eq!(None, (BigInt::from(i64::MIN) - BigInt::one()).to_i64());
+        assert!(!(-BigInt::zero()).is_negative());
+    }
+
+    #[test]
+    fn test_to_f64() {
+        // 2^100 + 2^47 + 1 rounds up to 2^100 + 2^48 only because of the trailing 1
+        let value = (BigUint::one() << 100) + (BigUint::one() << 47) + BigUint::one();
+
+        assert_eq!(2f64.powi(100) + 2f64.powi(48), value.to_f64());
+        assert_eq!(f64::INFINITY, (BigUint::one() << 1100).to_f64());
+    }
+
+    #[test]
+    fn test_conversions() {
+        let value = BigUint::from(0x0102030405060708090a0b0c0d0e0f10u128);
+
+        assert_eq!((1..=16).collect::<Vec<u8>>(), value.to_bytes_be());
+        assert_eq!((1..=16).rev().collect::<Vec<u8>>(), value.to_bytes_le());
+        assert_eq!(value, BigUint::from_bytes_be(&value.to_bytes_be()));
+        assert_eq!(value, BigUint::from_bytes_le(&value.to_bytes_le()));
+        assert_eq!(value, BigUint::from_bytes_be(&[0, 0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16]));
+        assert_eq!(vec![0], BigUint::zero().to_bytes_be());
+
+        assert_eq!("102030405060708090a0b0c0d0e0f10", format!("{:x}", value));
+        assert_eq!("0x0", format!("{:#x}", BigUint::zero()));
+        assert_eq!("-0xff", format!("{:#x}", BigInt::from(-255)));
+        assert_eq!(Ok(value), BigUint::from_hex("01020304 05060708\n090A0B0C 0D0E0F10"));
+        assert_eq!(Err(ParseBigIntError), BigUint::from_hex("0x10"));
+        assert_eq!(Err(ParseBigIntError), BigUint::from_hex(""));
+    }
+
+    #[test]
+    fn test_modular_arithmetic() {
+        let base = BigUint::from(0x123456789abcdef0123456789abcdefu128);
+        let exponent = BigUint::from(3u8).pow(200);
+
+        // An odd modulus (2^521 - 1, which uses Montgomery multiplication) and an even one
+        let odd_modulus = (BigUint::one() << 521) - BigUint::one();
+        let even_modulus = (BigUint::one() << 256) + (BigUint::one() << 64);
+
+        assert_eq!(
+            BigUint::from_hex("81a946c6ca0edf17163044fae92afaa30bbdcaeaa0c726964ba49f402df3bdced7555cfccd474b525eeef9ccb120544892b25dd344e850f6fb7228e63907bcc3c6").unwrap(),
+            base.modpow(&exponent, &odd_modulus)
+        );
+
+        assert_eq!(
+            BigUint::from_hex("eebb3c6540697de94bea86d3442a2ed0fd1fbfa3df62e4d79ff07535dba233ef").unwrap(),
+            base.modpow(&exponent, &even_modulus)
+        );
+
+        let mersenne_prime = BigUint::from(u128::MAX >> 1);
+
+        assert_eq!(Some("145835300108973627198589117470757804909".parse().unwrap()), BigUint::from(7u8).modinv(&mersenne_prime));
+        assert_eq!(None, BigUint::from(6u8).modinv(&BigUint::from(9u8)));
+        assert_eq!(BigUint::zero(), BigUint::from(5u8).modpow(&BigUint::from(3u8), &BigUint::one()));
+
+        let (gcd, x, y) = BigInt::from(240).extended_gcd(&BigInt::from(46));
+        assert_eq!((BigUint::from(2u8), BigInt::from(-9), BigInt::from(47)), (gcd, x, y));
+    }
+
+    #[test]
+    fn test_nth_root() {
+        let root: BigUint = "123456789123456789".parse().unwrap();
+        let power: BigUint = "28679718746395774374120706059393789284379149991153972603542457806511216795681720495294".parse().unwrap();
+
+        assert_eq!(root, power.nth_root(5));
+        assert_eq!(root, (&power - BigUint::from(12345u16)).nth_root(5));
+        assert_eq!(&root - BigUint::one(), (&power - BigUint::from(12346u16)).nth_root(5));
+        assert_eq!(BigUint::zero(), BigUint::zero().nth_root(3));
+
+        assert_eq!(Some(root.clone()), root.pow(5).exact_nth_root(5));
+        assert_eq!(None, (root.pow(5) + BigUint::one()).exact_nth_root(5));
+        assert_eq!(None, power.exact_nth_root(5));
+    }
+
+    #[test]
+    fn test_chinese_remainder() {
+        let congruences = [(2u8, 3u8), (3, 5), (2, 7)].map(|(a, m)| (BigUint::from(a), BigUint::from(m)));
+
+        assert_eq!(Some((BigUint::from(23u8), BigUint::from(105u8))), chinese_remainder(&congruences));
+        assert_eq!(Some((BigUint::zero(), BigUint::one())), chinese_remainder(&[]));
+
+        // 4 and 6 share a factor
+        assert_eq!(None, chinese_remainder(&[(BigUint::one(), BigUint::from(4u8)), (BigUint::one(), BigUint::from(6u8))]));
+
+        // Large moduli (the Mersenne primes 2^89 - 1, 2^107 - 1 and 2^127 - 1)
+        let moduli = [89, 107, 127].map(|bits| (BigUint::one() << bits) - BigUint::one());
+        let x: BigUint = "123456789012345678901234567890123456789012345678901234567890123456789".parse().unwrap();
+        let congruences: Vec<_> = moduli.iter().map(|m| (&x % m, m.clone())).collect();
+
+        assert_eq!(x, chinese_remainder(&congruences).unwrap().0);
+    }
+
+    #[test]
+    fn test_modular_arithmetic_against_u128() {
+        let mut rng = rand::thread_rng();
+
+        for _ in 0..1_000 {
+            // Keep everything below 2^64 so products fit in a u128
+            let modulus = rng.gen_range(1..u64::MAX) as u128;
+            let base = rng.gen::<u64>() as u128;
+            let exponent = rng.gen::<u64>() >> rng.gen_range(0..64);
+
+            let mut expected = 1 % modulus;
+
+            for i in (0..64).rev() {
+                expected = expected * expected % modulus;
+
+                if exponent >> i & 1 == 1 {
+                    expected = expected * (base % modulus) % modulus;
+                }
+            }
+
+            let (big_base, big_modulus) = (BigUint::from(base), BigUint::from(modulus));
+
+            assert_eq!(Some(expected), big_base.modpow(&BigUint::from(exponent), &big_modulus).to_u128());
+
+            match big_base.modinv(&big_modulus) {
+                Some(inverse) => assert_eq!(Some(1 % modulus), (&big_base * inverse % &big_modulus).to_u128()),
+                None => assert_ne!(BigUint::one(), big_base.gcd(&big_modulus)),
+            }
+
+            let (a, b) = (base as i128 * if rng.gen() { 1 } else { -1 }, modulus as i128);
+            let (gcd, x, y) = BigInt::from(a).extended_gcd(&BigInt::from(b));
+
+            assert_eq!(BigInt::from(gcd), BigInt::from(a) * x + BigInt::from(b) * y);
+
+            let value = rng.gen::<u128>() >> rng.gen_range(0..128);
+            let n = rng.gen_range(2..6);
+            let root = BigUint::from(value).nth_root(n).to_u128().unwrap();
+
+            assert!(root.pow(n) <= value);
+            assert!((root + 1).checked_pow(n).is_none_or(|power| power > value));
+
+            assert_eq!(value.to_be_bytes().to_vec(), {
+                let mut bytes = vec![0; 16];
+                let minimal = BigUint::from(value).to_bytes_be();
+                bytes[16 - minimal.len()..].copy_from_slice(&minimal);
+                bytes
+            });
+
+            assert_eq!(format!("{:x}", value), format!("{:x}", BigUint::from(value)));
+        }
+    }
+
+    #[test]
+    fn test_montgomery() {
+        let mut rng = rand::thread_rng();
+
+        for limbs in 1..6 {
+            let modulus = BigUint::from_limbs((0..limbs).map(|_| rng.gen::<u64>() | 1 | 1 << 63).collect());
+            let montgomery = Montgomery::new(&modulus);
+
+            let a = BigUint::from_limbs((0..limbs).map(|_| rng.gen()).collect()) % &modulus;
+            let b = BigUint::from_limbs((0..limbs).map(|_| rng.gen()).collect()) % &modulus;
+
+            let product = montgomery.multiply(&montgomery.to_montgomery(&a), &montgomery.to_montgomery(&b));
+
+            assert_eq!(&a * &b % &modulus, montgomery.from_montgomery(&product));
+            assert_eq!(a, montgomery.from_montgomery(&montgomery.to_montgomery(&a)));
+        }
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "less than the modulus")]
+    fn test_montgomery_oversized_operand() {
+        let montgomery = Montgomery::new(&BigUint::from(97u64));
+
+        montgomery.multiply(&BigUint::from(1u64 << 40), &BigUint::one());
+    }
+}
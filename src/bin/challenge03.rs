@@ -1,21 +1,14 @@
 use std::error::Error;
-use cryptopals::text::englishiness;
+use cryptopals::text;
+use cryptopals::xor::{break_single_byte_xor, single_byte_xor};
 
 const CIPHERTEXT: &str = "1b37373331363f78151b7f2b783431333d78397828372d363c78373e783a393b3736";
 
 fn main() -> Result<(), Box<dyn Error>> {
     let ciphertext = hex::decode(CIPHERTEXT)?;
 
-    let (key, cleartext) = (0..=u8::MAX)
-        .map(|key| {
-            let cleartext: String = ciphertext.iter()
-                .filter_map(|b| char::from_u32((b ^ key) as u32))
-                .collect();
-
-            (key, cleartext)
-        })
-        .max_by_key(|(_, cleartext)| englishiness(cleartext))
-        .unwrap();
+    let (key, _) = break_single_byte_xor(&ciphertext, &text::english());
+    let cleartext = String::from_utf8_lossy(&single_byte_xor(&ciphertext, key)).into_owned();
 
     println!("Key = {:#04x}; cleartext = {}", key, cleartext);
 
@@ -2,7 +2,8 @@ use std::error::Error;
 use std::fs::File;
 use std::{env, io};
 use std::io::BufRead;
-use cryptopals::text::englishiness;
+use cryptopals::text;
+use cryptopals::xor::{break_single_byte_xor, single_byte_xor};
 
 fn main() -> Result<(), Box<dyn Error>> {
     let args: Vec<String> = env::args().collect();
@@ -15,14 +16,18 @@ fn main() -> Result<(), Box<dyn Error>> {
             .map(hex::decode)
             .collect::<Result<_, _>>()?;
 
-        let (ciphertext, key, cleartext) = ciphertexts.iter()
+        let scorer = text::english();
+
+        let (ciphertext, key, _) = ciphertexts.iter()
             .map(|ciphertext| {
-                let (key, cleartext) = guess_key(ciphertext);
-                (ciphertext, key, cleartext)
+                let (key, score) = break_single_byte_xor(ciphertext, &scorer);
+                (ciphertext, key, score)
             })
-            .max_by_key(|(_, _, cleartext)| englishiness(cleartext))
+            .max_by(|(_, _, a), (_, _, b)| a.total_cmp(b))
             .unwrap();
 
+        let cleartext = String::from_utf8_lossy(&single_byte_xor(ciphertext, key)).into_owned();
+
         println!("decrypt({}, {:#04x}): {}", hex::encode(ciphertext), key, cleartext);
 
         Ok(())
@@ -30,16 +35,3 @@ fn main() -> Result<(), Box<dyn Error>> {
         Err("Usage: challenge04 PATH_TO_INPUT_FILE".into())
     }
 }
-
-fn guess_key(ciphertext: &[u8]) -> (u8, String) {
-    (0..=u8::MAX)
-        .map(|key| {
-            let cleartext: String = ciphertext.iter()
-                .filter_map(|b| char::from_u32((b ^ key) as u32))
-                .collect();
-
-            (key, cleartext)
-        })
-        .max_by_key(|(_, cleartext)| englishiness(cleartext))
-        .unwrap()
-}
@@ -1,6 +1,7 @@
 use std::{env, fs};
 use std::error::Error;
-use cryptopals::text::englishiness;
+use cryptopals::text::{ChiSquared, PrintableAscii};
+use cryptopals::xor::break_single_byte_xor;
 
 fn main() -> Result<(), Box<dyn Error>> {
     let args: Vec<String> = env::args().collect();
@@ -31,30 +32,23 @@ fn main() -> Result<(), Box<dyn Error>> {
 }
 
 fn guess_key(ciphertext: &[u8], key_length: usize) -> Vec<u8> {
-    let mut key = vec![0; key_length];
-
-    for i in 0..key_length {
-        let bytes_at_block_position: Vec<u8> = ciphertext.iter()
-            .skip(i)
-            .step_by(key_length)
-            .copied()
-            .collect();
-
-        let (key_byte_at_position, _) = (u8::MIN..=u8::MAX)
-            .map(|key| {
-                let cleartext: String = bytes_at_block_position.iter()
-                    .filter_map(|b| char::from_u32((b ^ key) as u32))
-                    .collect();
-
-                (key, cleartext)
-            })
-            .max_by_key(|(_, cleartext)| englishiness(cleartext))
-            .unwrap();
-
-        key[i] = key_byte_at_position;
-    }
-
-    key
+    // Each key byte covers every key_length-th byte of the plaintext, which isn't contiguous text,
+    // so stick to character frequencies rather than n-grams
+    let scorer = (ChiSquared::english(), PrintableAscii);
+
+    (0..key_length)
+        .map(|i| {
+            let bytes_at_block_position: Vec<u8> = ciphertext.iter()
+                .skip(i)
+                .step_by(key_length)
+                .copied()
+                .collect();
+
+            let (key_byte_at_position, _) = break_single_byte_xor(&bytes_at_block_position, &scorer);
+
+            key_byte_at_position
+        })
+        .collect()
 }
 
 fn probable_key_lengths(ciphertext: &[u8], min_length: usize, max_length: usize) -> Vec<usize> {
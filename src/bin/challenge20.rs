@@ -1,5 +1,7 @@
 use std::error::Error;
 use rand::RngCore;
+use cryptopals::text::{ChiSquared, PrintableAscii};
+use cryptopals::xor::break_single_byte_xor;
 
 const ENCODED_CLEARTEXT: &str = include_str!("../../data/challenge20.txt");
 
@@ -25,18 +27,15 @@ fn main() -> Result<(), Box<dyn Error>> {
         .min()
         .unwrap();
 
+    let scorer = (ChiSquared::english(), PrintableAscii);
     let mut keystream = vec![0; shortest_ciphertext_length];
 
     for i in 0..keystream.len() {
-        keystream[i] = (u8::MIN..=u8::MAX)
-            .max_by_key(|candidate| {
-                let column: String = ciphertexts.iter()
-                    .map(|ciphertext| (ciphertext[i] ^ candidate) as char)
-                    .collect();
-
-                cryptopals::text::englishiness(&column)
-            })
-            .unwrap();
+        let column: Vec<u8> = ciphertexts.iter()
+            .map(|ciphertext| ciphertext[i])
+            .collect();
+
+        keystream[i] = break_single_byte_xor(&column, &scorer).0;
     }
 
     ciphertexts.iter()
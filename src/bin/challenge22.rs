@@ -1,6 +1,7 @@
 use std::time::{SystemTime, UNIX_EPOCH};
 use rand::RngCore;
 use cryptopals::random::MersenneTwister;
+use cryptopals::seed_search::{find_seeds_from_outputs, SeedSpace};
 
 fn main() {
     let (seed_time, sweep_time, random_value) = {
@@ -20,11 +21,11 @@ fn main() {
         (seed_time, sweep_time, MersenneTwister::new(seed_time as u32).next_u32())
     };
 
-    for t in sweep_time - 7_260_000..=sweep_time {
-        if MersenneTwister::new(t as u32).next_u32() == random_value {
-            println!("Seed: {}", t);
-            assert_eq!(seed_time, t);
-            break;
-        }
-    }
+    let seeds = find_seeds_from_outputs(
+        &[random_value],
+        SeedSpace::Window { latest: sweep_time as u32, before: 7_260_000 },
+    );
+
+    println!("Seed: {:?}", seeds);
+    assert_eq!(vec![seed_time as u32], seeds);
 }
@@ -41,6 +41,13 @@ impl CookieApi {
         Self { key: cryptopals::text::random_word() }
     }
 
+    /// Picks the key from the bundled word list with the given generator, so the key is the same
+    /// on every machine for a given seed.
+    #[cfg(test)]
+    fn with_rng(rng: &mut impl rand::Rng) -> Self {
+        Self { key: cryptopals::text::WordList::bundled().random_word(rng).to_string() }
+    }
+
     fn generate_signed_cookie(&self) -> (String, Vec<u8>) {
         const COOKIE_TEXT: &str =
             "comment1=cooking%20MCs;userdata=foo;comment2=%20like%20a%20pound%20of%20bacon";
@@ -65,11 +72,13 @@ impl CookieApi {
 
 #[cfg(test)]
 mod test {
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
     use crate::CookieApi;
 
     #[test]
     fn test_validate_cookie() {
-        let cookie_api = CookieApi::new();
+        let cookie_api = CookieApi::with_rng(&mut StdRng::seed_from_u64(30));
         let (cookie, mut mac) = cookie_api.generate_signed_cookie();
 
         assert!(cookie_api.validate_cookie(&cookie, &mac));
@@ -0,0 +1,21 @@
+use cryptopals::bigint::BigUint;
+use cryptopals::dh::{derive_aes_key, DhGroup};
+
+fn main() {
+    for group in [
+        DhGroup::new(BigUint::from(37u8), BigUint::from(5u8), BigUint::from(36u8)),
+        DhGroup::modp_1536().clone(),
+    ] {
+        let alice = group.generate_keypair(&mut rand::thread_rng());
+        let bob = group.generate_keypair(&mut rand::thread_rng());
+
+        let alice_secret = alice.shared_secret(&group, bob.public_key());
+        let bob_secret = bob.shared_secret(&group, alice.public_key());
+
+        assert_eq!(alice_secret, bob_secret);
+
+        println!("p = {}", group.p);
+        println!("Shared secret: {}", alice_secret);
+        println!("Derived key: {}", hex::encode(derive_aes_key(&alice_secret)));
+    }
+}
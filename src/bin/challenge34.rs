@@ -0,0 +1,17 @@
+use cryptopals::dh::DhGroup;
+use cryptopals::mitm::{simulate, Eavesdropper, KeyFixing};
+
+fn main() {
+    let messages: [&[u8]; 2] = [b"Hello, Bob!", b"Nobody else can read this."];
+
+    let echoes = simulate(DhGroup::modp_1536(), &messages, &mut Eavesdropper::default());
+    println!("Honest exchange, Alice got back: {:?}", echoes.iter().flatten().map(|m| String::from_utf8_lossy(m)).collect::<Vec<_>>());
+
+    let mut mallory = KeyFixing::default();
+    let echoes = simulate(DhGroup::modp_1536(), &messages, &mut mallory);
+    println!("Key fixing, Alice got back: {:?}", echoes.iter().flatten().map(|m| String::from_utf8_lossy(m)).collect::<Vec<_>>());
+
+    for (direction, message) in &mallory.recovered {
+        println!("Mallory read {:?}: {}", direction, String::from_utf8_lossy(message));
+    }
+}
@@ -0,0 +1,17 @@
+use cryptopals::dh::DhGroup;
+use cryptopals::mitm::{simulate, MaliciousGenerator, ParameterInjection};
+
+fn main() {
+    let messages: [&[u8]; 2] = [b"Hello, Bob!", b"Nobody else can read this."];
+
+    for generator in [MaliciousGenerator::One, MaliciousGenerator::P, MaliciousGenerator::PMinusOne] {
+        let mut mallory = ParameterInjection::new(generator);
+        let echoes = simulate(DhGroup::modp_1536(), &messages, &mut mallory);
+
+        println!("g = {:?}, Alice got back: {:?}", generator, echoes.iter().flatten().map(|m| String::from_utf8_lossy(m)).collect::<Vec<_>>());
+
+        for (direction, message) in &mallory.recovered {
+            println!("  Mallory read {:?}: {}", direction, String::from_utf8_lossy(message));
+        }
+    }
+}
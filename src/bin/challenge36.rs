@@ -0,0 +1,72 @@
+use std::env;
+use std::error::Error;
+use std::net::{TcpListener, TcpStream};
+use std::thread;
+use cryptopals::srp::{login, Server, SrpParameters};
+
+const USAGE: &str = "Usage: challenge36 [server ADDRESS USERNAME PASSWORD | client ADDRESS USERNAME PASSWORD]";
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    match args.iter().map(String::as_str).collect::<Vec<_>>().as_slice() {
+        // Serves one user until killed, so a client can log in from another process
+        ["server", address, username, password] => {
+            let mut server = Server::new(SrpParameters::standard().clone());
+            server.register(username, password);
+
+            let listener = TcpListener::bind(address)?;
+            println!("Listening on {}", listener.local_addr()?);
+
+            for stream in listener.incoming() {
+                let stream = stream?;
+                let peer = stream.peer_addr()?;
+
+                match server.handle(stream) {
+                    Ok(true) => println!("{}: logged in", peer),
+                    Ok(false) => println!("{}: login failed", peer),
+                    Err(error) => println!("{}: {}", peer, error),
+                }
+            }
+        }
+        ["client", address, username, password] => {
+            let stream = TcpStream::connect(address)?;
+            let accepted = login(SrpParameters::standard(), stream, username, password)?;
+
+            println!("{}", if accepted { "Logged in" } else { "Login failed" });
+        }
+        [] => {
+            // With no arguments, run both ends in this process over loopback
+            let mut server = Server::new(SrpParameters::standard().clone());
+            server.register("alice@example.com", "correct horse battery staple");
+
+            let listener = TcpListener::bind("127.0.0.1:0")?;
+            let address = listener.local_addr()?;
+
+            let attempts = [
+                ("alice@example.com", "correct horse battery staple"),
+                ("alice@example.com", "Tr0ub4dor&3"),
+            ];
+
+            thread::scope(|scope| -> Result<(), Box<dyn Error>> {
+                let server = &server;
+
+                scope.spawn(move || {
+                    for stream in listener.incoming().take(attempts.len()) {
+                        server.handle(stream.unwrap()).unwrap();
+                    }
+                });
+
+                for (username, password) in attempts {
+                    let accepted = login(SrpParameters::standard(), TcpStream::connect(address)?, username, password)?;
+                    println!("{} with password {:?}: {}", username, password, if accepted { "logged in" } else { "login failed" });
+                }
+
+                Ok(())
+            })?;
+        }
+        _ => return Err(USAGE.into()),
+    }
+
+    Ok(())
+}
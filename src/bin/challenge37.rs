@@ -0,0 +1,31 @@
+use std::error::Error;
+use std::net::{TcpListener, TcpStream};
+use std::thread;
+use cryptopals::srp::{zero_key_login, Server, SrpParameters};
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let parameters = SrpParameters::standard();
+
+    let mut server = Server::without_key_checks(parameters.clone());
+    server.register("alice@example.com", &cryptopals::text::random_word());
+
+    let listener = TcpListener::bind("127.0.0.1:0")?;
+    let address = listener.local_addr()?;
+
+    thread::scope(|scope| -> Result<(), Box<dyn Error>> {
+        let server = &server;
+
+        scope.spawn(move || {
+            for stream in listener.incoming().take(3) {
+                server.handle(stream.unwrap()).unwrap();
+            }
+        });
+
+        for multiple in 0..3 {
+            let accepted = zero_key_login(parameters, TcpStream::connect(address)?, "alice@example.com", multiple)?;
+            println!("A = {} * N: {}", multiple, if accepted { "logged in" } else { "login failed" });
+        }
+
+        Ok(())
+    })
+}
@@ -0,0 +1,42 @@
+use std::error::Error;
+use std::net::{TcpListener, TcpStream};
+use std::thread;
+use cryptopals::srp::{capture_simplified_login, simplified_login, SrpParameters};
+use cryptopals::text::WordList;
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let parameters = SrpParameters::standard();
+    let password = cryptopals::text::random_word();
+
+    // The client logs in to what it thinks is the real server
+    let listener = TcpListener::bind("127.0.0.1:0")?;
+    let address = listener.local_addr()?;
+
+    let captured = thread::scope(|scope| -> Result<_, Box<dyn Error>> {
+        let mallory = scope.spawn(move || capture_simplified_login(parameters, listener.accept()?.0));
+
+        simplified_login(parameters, TcpStream::connect(address)?, "alice@example.com", &password)?;
+
+        Ok(mallory.join().unwrap()?)
+    })?;
+
+    println!("Captured proof {} from {}", hex::encode(&captured.proof), captured.username);
+
+    let attack = captured.crack(parameters, WordList::system().words());
+
+    match &attack.password {
+        Some(recovered) => println!("Recovered password: {}", recovered),
+        None => println!("Password not in the word list"),
+    }
+
+    println!(
+        "{} guesses in {:.2?} ({:.0} guesses per second)",
+        attack.guesses,
+        attack.elapsed,
+        attack.guesses_per_second(),
+    );
+
+    assert_eq!(Some(&password), attack.password.as_ref());
+
+    Ok(())
+}
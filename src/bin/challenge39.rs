@@ -0,0 +1,17 @@
+use cryptopals::bigint::BigUint;
+use cryptopals::rsa::RsaPrivateKey;
+
+fn main() {
+    let key = RsaPrivateKey::generate(&mut rand::thread_rng(), 1024, &BigUint::from(3u8));
+    let public_key = key.public_key();
+
+    let message = BigUint::from_bytes_be(b"Textbook RSA, with e = 3");
+    let ciphertext = public_key.encrypt(&message);
+    let decrypted = key.decrypt(&ciphertext);
+
+    println!("n = {:x}", public_key.n());
+    println!("Ciphertext: {:x}", ciphertext);
+    println!("Decrypted: {}", String::from_utf8_lossy(&decrypted.to_bytes_be()));
+
+    assert_eq!(message, decrypted);
+}
@@ -0,0 +1,22 @@
+use cryptopals::bigint::BigUint;
+use cryptopals::rsa::{broadcast_attack, RsaPrivateKey};
+
+fn main() {
+    let message = BigUint::from_bytes_be(b"The same message, sent to three recipients");
+
+    // Each recipient has their own e = 3 key, and the message is encrypted without padding
+    let ciphertexts: Vec<_> = (0..3)
+        .map(|_| {
+            let key = RsaPrivateKey::generate(&mut rand::thread_rng(), 1024, &BigUint::from(3u8));
+            let public_key = key.public_key().clone();
+            let ciphertext = public_key.encrypt(&message);
+
+            (public_key, ciphertext)
+        })
+        .collect();
+
+    let recovered = broadcast_attack(&ciphertexts).expect("Attack failed");
+
+    println!("Recovered: {}", String::from_utf8_lossy(&recovered.to_bytes_be()));
+    assert_eq!(message, recovered);
+}
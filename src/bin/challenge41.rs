@@ -0,0 +1,18 @@
+use cryptopals::bigint::BigUint;
+use cryptopals::rsa::{unpadded_message_recovery, DecryptionOracle, RsaPrivateKey};
+
+fn main() {
+    let mut oracle = DecryptionOracle::new(RsaPrivateKey::generate(&mut rand::thread_rng(), 1024, &BigUint::from(65537u32)));
+
+    // A client submits its ciphertext, which an attacker captures on the way
+    let message = BigUint::from_bytes_be(br#"{"time": 1356304276, "social": "555-55-5555"}"#);
+    let ciphertext = oracle.public_key().encrypt(&message);
+
+    oracle.decrypt(&ciphertext).unwrap();
+    assert!(oracle.decrypt(&ciphertext).is_none(), "Oracle decrypted the same ciphertext twice");
+
+    let recovered = unpadded_message_recovery(&mut oracle, &ciphertext, &mut rand::thread_rng()).expect("Oracle refused");
+
+    println!("Recovered: {}", String::from_utf8_lossy(&recovered.to_bytes_be()));
+    assert_eq!(message, recovered);
+}
@@ -0,0 +1,13 @@
+use cryptopals::crime::{CompressionOracle, OracleCipher, recover_session_id};
+
+const SESSION_ID: &str = "TmV2ZXIgcmV2ZWFsIHRoZSBXdS1UYW5nIFNlY3JldCE=";
+
+fn main() {
+    for cipher in [OracleCipher::Ctr, OracleCipher::Cbc] {
+        let oracle = CompressionOracle::new(SESSION_ID, cipher);
+        let session_id = recover_session_id(|body| oracle.ciphertext_length(body));
+
+        println!("Recovered session ID ({:?}): {}", cipher, String::from_utf8_lossy(&session_id));
+        assert_eq!(SESSION_ID.as_bytes(), session_id);
+    }
+}
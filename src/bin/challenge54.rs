@@ -0,0 +1,43 @@
+use std::error::Error;
+use std::fs::File;
+use std::path::Path;
+use std::{env, io};
+use cryptopals::cheap_hash::{BLOCK_SIZE, CheapHash};
+use cryptopals::herding::Diamond;
+
+const STATE_SIZE: usize = 3;
+const DIAMOND_DEPTH: usize = 10;
+
+fn main() -> Result<(), Box<dyn Error>> {
+    // Building the diamond is the expensive part, so optionally cache it between runs
+    let diamond = if let Some(path) = env::args().nth(1) {
+        if Path::new(&path).exists() {
+            Diamond::<STATE_SIZE>::read_from(&mut io::BufReader::new(File::open(&path)?))?
+        } else {
+            let diamond = Diamond::build(DIAMOND_DEPTH);
+            diamond.write_to(&mut io::BufWriter::new(File::create(&path)?))?;
+
+            diamond
+        }
+    } else {
+        Diamond::build(DIAMOND_DEPTH)
+    };
+
+    const PREFIX: &[u8] = b"2022 World Series: Astros 4, Phillies 2";
+    let prediction = diamond.prediction(PREFIX.len().div_ceil(BLOCK_SIZE));
+
+    println!("Prediction: {}", hex::encode(prediction));
+
+    let message = diamond.herd(PREFIX);
+
+    let hash = {
+        let mut hash = CheapHash::<STATE_SIZE>::default();
+        hash.update(&message);
+        hash.finish()
+    };
+
+    println!("hash({}) = {}", hex::encode(&message), hex::encode(hash));
+    assert_eq!(prediction, hash);
+
+    Ok(())
+}
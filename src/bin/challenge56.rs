@@ -0,0 +1,38 @@
+use std::env;
+use std::error::Error;
+use std::time::Instant;
+use rand::RngCore;
+use cryptopals::rc4::{rc4_transform, recover_secret_suffix};
+
+const ENCODED_COOKIE: &str = "QkUgU1VSRSBUTyBEUklOSyBZT1VSIE9WQUxUSU5F";
+
+fn main() -> Result<(), Box<dyn Error>> {
+    // 2^24 samples per prefix length is enough to recover the whole cookie reliably, but is also
+    // pretty slow; fewer samples will still recover most (but probably not all) of the cookie.
+    let samples = match env::args().nth(1) {
+        Some(samples) => samples.parse()?,
+        None => 1 << 24,
+    };
+
+    let cookie = radix64::STD.decode(ENCODED_COOKIE)?;
+
+    let oracle = |request: &[u8]| {
+        let mut key = [0; 16];
+        rand::thread_rng().fill_bytes(&mut key);
+
+        let mut plaintext = Vec::from(request);
+        plaintext.extend_from_slice(&cookie);
+
+        rc4_transform(&plaintext, &key)
+    };
+
+    let start = Instant::now();
+
+    // Z16 is biased toward 0xf0 and Z32 toward 0xe0
+    let recovered_cookie = recover_secret_suffix(oracle, cookie.len(), &[15, 31], samples);
+
+    println!("Recovered cookie in {:?}: {}", start.elapsed(), String::from_utf8_lossy(&recovered_cookie));
+    assert_eq!(cookie, recovered_cookie);
+
+    Ok(())
+}
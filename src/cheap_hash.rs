@@ -0,0 +1,132 @@
+use aes::Aes128;
+use aes::cipher::generic_array::GenericArray;
+use aes::cipher::{BlockEncrypt, KeyInit};
+
+pub const BLOCK_SIZE: usize = 16;
+
+/// A deliberately weak Merkle-Damgård hash with an `N`-byte state. Each message block is used as
+/// an AES-128 key to encrypt the (zero-padded) current state, and the result is truncated back
+/// down to `N` bytes. With a small enough `N`, finding collisions by brute force is cheap.
+pub struct CheapHash<const N: usize> {
+    state: [u8; N],
+    buffer: [u8; BLOCK_SIZE],
+    buffer_write_index: usize,
+    message_length_bytes: usize,
+}
+
+impl<const N: usize> CheapHash<N> {
+    pub fn with_initial_state(state: &[u8], message_length_bytes: usize) -> Self {
+        assert_eq!(N, state.len());
+
+        Self {
+            state: state.try_into().unwrap(),
+
+            buffer: [0; BLOCK_SIZE],
+            buffer_write_index: 0,
+            message_length_bytes,
+        }
+    }
+
+    pub fn update(&mut self, bytes: &[u8]) {
+        let mut read_index = 0;
+
+        while read_index < bytes.len() {
+            let bytes_to_read =
+                (bytes.len() - read_index).min(self.buffer.len() - self.buffer_write_index);
+
+            self.buffer[self.buffer_write_index..self.buffer_write_index + bytes_to_read]
+                .clone_from_slice(&bytes[read_index..read_index + bytes_to_read]);
+
+            read_index += bytes_to_read;
+            self.buffer_write_index += bytes_to_read;
+
+            if self.buffer_write_index == self.buffer.len() {
+                self.state = Self::compress(&self.state, &self.buffer);
+                self.buffer_write_index = 0;
+            }
+        }
+
+        self.message_length_bytes += bytes.len();
+    }
+
+    pub fn compress(state: &[u8; N], block: &[u8; BLOCK_SIZE]) -> [u8; N] {
+        assert!(N <= BLOCK_SIZE);
+
+        let cipher = Aes128::new_from_slice(block).unwrap();
+
+        let mut padded_state = [0; BLOCK_SIZE];
+        padded_state[..N].clone_from_slice(state);
+
+        let mut padded_state = GenericArray::from(padded_state);
+        cipher.encrypt_block(&mut padded_state);
+
+        padded_state[..N].try_into().unwrap()
+    }
+
+    /// Returns the intermediate state after all complete blocks seen so far have been compressed.
+    pub fn state(&self) -> [u8; N] {
+        self.state
+    }
+
+    pub fn finish(mut self) -> [u8; N] {
+        self.update(&Self::padding(self.message_length_bytes));
+        self.state
+    }
+
+    pub fn padding(message_length_bytes: usize) -> Vec<u8> {
+        let padding_length = (8 - (message_length_bytes + 1) as isize).rem_euclid(BLOCK_SIZE as isize) as usize;
+
+        let mut padding = vec![0; padding_length + 1];
+        padding[0] = 0x80;
+
+        let message_length_bits = message_length_bytes as u64 * 8;
+        padding.extend_from_slice(&message_length_bits.to_be_bytes());
+
+        padding
+    }
+}
+
+impl<const N: usize> Default for CheapHash<N> {
+    fn default() -> Self {
+        Self::with_initial_state(&[0; N], 0)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::cheap_hash::{BLOCK_SIZE, CheapHash};
+
+    #[test]
+    fn test_padding() {
+        for message_length in 0..64 {
+            assert_eq!(0, (message_length + CheapHash::<2>::padding(message_length).len()) % BLOCK_SIZE);
+        }
+    }
+
+    #[test]
+    fn test_update() {
+        let message = b"This message spans more than one block, and also doesn't end on a block boundary";
+
+        let one_shot = {
+            let mut hash = CheapHash::<3>::default();
+            hash.update(message);
+            hash.finish()
+        };
+
+        let incremental = {
+            let mut hash = CheapHash::<3>::default();
+            message.chunks(7).for_each(|chunk| hash.update(chunk));
+            hash.finish()
+        };
+
+        assert_eq!(one_shot, incremental);
+
+        let different = {
+            let mut hash = CheapHash::<3>::default();
+            hash.update(b"Something else entirely");
+            hash.finish()
+        };
+
+        assert_ne!(one_shot, different);
+    }
+}
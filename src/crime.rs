@@ -0,0 +1,137 @@
+use rand::RngCore;
+use crate::aes::{aes_cbc_encrypt, aes_ctr_transform};
+use crate::deflate::deflate;
+
+const SESSION_ID_PREFIX: &[u8] = b"sessionid=";
+const BASE64_ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/=";
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum OracleCipher {
+    Ctr,
+    Cbc,
+}
+
+/// Formats an HTTP-like request that carries a secret session ID alongside an
+/// attacker-controlled body, then compresses and encrypts it under a fresh key. Only the length
+/// of the ciphertext is revealed.
+pub struct CompressionOracle {
+    session_id: String,
+    cipher: OracleCipher,
+}
+
+impl CompressionOracle {
+    pub fn new(session_id: &str, cipher: OracleCipher) -> Self {
+        Self {
+            session_id: session_id.to_string(),
+            cipher,
+        }
+    }
+
+    pub fn format_request(&self, body: &[u8]) -> Vec<u8> {
+        let mut request = format!(
+            "POST / HTTP/1.1\nHost: hapless.com\nCookie: sessionid={}\nContent-Length: {}\n",
+            self.session_id,
+            body.len()
+        ).into_bytes();
+
+        request.extend_from_slice(body);
+
+        request
+    }
+
+    pub fn ciphertext_length(&self, body: &[u8]) -> usize {
+        let compressed = deflate(&self.format_request(body));
+
+        let mut key = [0; 16];
+        rand::thread_rng().fill_bytes(&mut key);
+
+        let ciphertext = match self.cipher {
+            OracleCipher::Ctr => aes_ctr_transform(&compressed, &key, rand::thread_rng().next_u64()),
+
+            OracleCipher::Cbc => {
+                let mut iv = [0; 16];
+                rand::thread_rng().fill_bytes(&mut iv);
+
+                aes_cbc_encrypt(&compressed, &key, &iv)
+            }
+        };
+
+        ciphertext.len()
+    }
+}
+
+/// Recovers a base64 session ID from a compression oracle one character at a time. A correct
+/// guess extends the match against the real session ID, and so compresses slightly better than
+/// an incorrect guess.
+///
+/// A single character's difference is usually less than a byte after compression, and much less
+/// than a block under CBC, so each guess is preceded by a run of incompressible filler bytes. The
+/// filler is lengthened until exactly one candidate stands out from the rest (i.e. until the
+/// compressed length of the right guess sits just below a byte or block boundary).
+pub fn recover_session_id(oracle: impl Fn(&[u8]) -> usize) -> Vec<u8> {
+    // Bytes outside of ASCII don't appear anywhere else in the request and never repeat within
+    // the filler, so the compressor can't do anything with them
+    const MAX_FILLER_LENGTH: usize = 128;
+    let filler: Vec<u8> = (0x80..=0xff).collect();
+
+    let mut session_id = vec![];
+
+    loop {
+        let next_character = (0..=MAX_FILLER_LENGTH).find_map(|filler_length| {
+            let lengths: Vec<(u8, usize)> = BASE64_ALPHABET.iter()
+                .map(|&candidate| {
+                    let mut body = Vec::from(&filler[..filler_length]);
+                    body.extend_from_slice(SESSION_ID_PREFIX);
+                    body.extend_from_slice(&session_id);
+                    body.push(candidate);
+
+                    (candidate, oracle(&body))
+                })
+                .collect();
+
+            let shortest = lengths.iter().map(|(_, length)| *length).min().unwrap();
+            let mut best_candidates = lengths.iter().filter(|(_, length)| *length == shortest);
+
+            match (best_candidates.next(), best_candidates.next()) {
+                (Some((candidate, _)), None) => Some(*candidate),
+                _ => None,
+            }
+        });
+
+        // Once we've run out of session ID, no candidate will ever compress better than the others
+        if let Some(next_character) = next_character {
+            session_id.push(next_character);
+        } else {
+            break;
+        }
+    }
+
+    session_id
+}
+
+#[cfg(test)]
+mod test {
+    use crate::crime::{CompressionOracle, OracleCipher, recover_session_id};
+
+    const SESSION_ID: &str = "TmV2ZXIgcmV2ZWFsIHRoZSBXdS1UYW5nIFNlY3JldCE=";
+
+    #[test]
+    fn test_recover_session_id_ctr() {
+        let oracle = CompressionOracle::new(SESSION_ID, OracleCipher::Ctr);
+
+        assert_eq!(
+            SESSION_ID.as_bytes(),
+            recover_session_id(|body| oracle.ciphertext_length(body))
+        );
+    }
+
+    #[test]
+    fn test_recover_session_id_cbc() {
+        let oracle = CompressionOracle::new(SESSION_ID, OracleCipher::Cbc);
+
+        assert_eq!(
+            SESSION_ID.as_bytes(),
+            recover_session_id(|body| oracle.ciphertext_length(body))
+        );
+    }
+}
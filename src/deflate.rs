@@ -0,0 +1,233 @@
+use std::collections::HashMap;
+
+const WINDOW_SIZE: usize = 32768;
+const MIN_MATCH_LENGTH: usize = 3;
+const MAX_MATCH_LENGTH: usize = 258;
+const MAX_CHAIN_LENGTH: usize = 256;
+
+const END_OF_BLOCK: u16 = 256;
+
+const LENGTH_BASES: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+    163, 195, 227, 258,
+];
+
+const LENGTH_EXTRA_BITS: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+
+const DISTANCE_BASES: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+    2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+
+const DISTANCE_EXTRA_BITS: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13, 13,
+];
+
+#[derive(Debug, Eq, PartialEq)]
+enum Token {
+    Literal(u8),
+    Match { length: usize, distance: usize },
+}
+
+/// Compresses the given bytes into a raw DEFLATE stream (RFC 1951) consisting of a single block
+/// that uses the fixed Huffman codes. Repeated strings are found with a greedy LZ77 search over
+/// hash chains.
+pub fn deflate(bytes: &[u8]) -> Vec<u8> {
+    let mut writer = BitWriter::default();
+
+    // BFINAL = 1, BTYPE = 01 (fixed Huffman codes)
+    writer.write_bits(1, 1);
+    writer.write_bits(1, 2);
+
+    for token in lz77(bytes) {
+        match token {
+            Token::Literal(literal) => write_literal_length_symbol(&mut writer, literal as u16),
+
+            Token::Match { length, distance } => {
+                let length_index = LENGTH_BASES.iter().rposition(|&base| base as usize <= length).unwrap();
+
+                write_literal_length_symbol(&mut writer, 257 + length_index as u16);
+                writer.write_bits(
+                    (length - LENGTH_BASES[length_index] as usize) as u32,
+                    LENGTH_EXTRA_BITS[length_index] as u32,
+                );
+
+                let distance_index = DISTANCE_BASES.iter().rposition(|&base| base as usize <= distance).unwrap();
+
+                writer.write_huffman_code(distance_index as u32, 5);
+                writer.write_bits(
+                    (distance - DISTANCE_BASES[distance_index] as usize) as u32,
+                    DISTANCE_EXTRA_BITS[distance_index] as u32,
+                );
+            }
+        }
+    }
+
+    write_literal_length_symbol(&mut writer, END_OF_BLOCK);
+
+    writer.finish()
+}
+
+fn lz77(bytes: &[u8]) -> Vec<Token> {
+    let mut tokens = vec![];
+
+    // Maps every three-byte sequence to the most recent position at which it appeared; `previous`
+    // then links each position back to the last occurrence of the same sequence before it.
+    let mut heads: HashMap<&[u8], usize> = HashMap::new();
+    let mut previous: Vec<Option<usize>> = vec![None; bytes.len()];
+
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let (length, distance) = if i + MIN_MATCH_LENGTH <= bytes.len() {
+            longest_match(bytes, i, &heads, &previous)
+        } else {
+            (0, 0)
+        };
+
+        if length >= MIN_MATCH_LENGTH {
+            tokens.push(Token::Match { length, distance });
+
+            for position in i..i + length {
+                insert_position(bytes, position, &mut heads, &mut previous);
+            }
+
+            i += length;
+        } else {
+            tokens.push(Token::Literal(bytes[i]));
+            insert_position(bytes, i, &mut heads, &mut previous);
+
+            i += 1;
+        }
+    }
+
+    tokens
+}
+
+fn insert_position<'a>(bytes: &'a [u8], position: usize, heads: &mut HashMap<&'a [u8], usize>, previous: &mut [Option<usize>]) {
+    if position + MIN_MATCH_LENGTH <= bytes.len() {
+        previous[position] = heads.insert(&bytes[position..position + MIN_MATCH_LENGTH], position);
+    }
+}
+
+fn longest_match(bytes: &[u8], position: usize, heads: &HashMap<&[u8], usize>, previous: &[Option<usize>]) -> (usize, usize) {
+    let max_length = MAX_MATCH_LENGTH.min(bytes.len() - position);
+
+    let mut best = (0, 0);
+    let mut candidate = heads.get(&bytes[position..position + MIN_MATCH_LENGTH]).copied();
+    let mut chain_length = 0;
+
+    while let Some(start) = candidate {
+        if position - start > WINDOW_SIZE || chain_length >= MAX_CHAIN_LENGTH {
+            break;
+        }
+
+        let length = bytes[start..].iter()
+            .zip(bytes[position..position + max_length].iter())
+            .take_while(|(a, b)| a == b)
+            .count();
+
+        // Candidates get further away as we walk the chain, so only take strictly longer matches
+        if length > best.0 {
+            best = (length, position - start);
+
+            if length == max_length {
+                break;
+            }
+        }
+
+        candidate = previous[start];
+        chain_length += 1;
+    }
+
+    best
+}
+
+fn write_literal_length_symbol(writer: &mut BitWriter, symbol: u16) {
+    // Fixed literal/length codes from RFC 1951 section 3.2.6
+    match symbol {
+        0..=143 => writer.write_huffman_code(0b00110000 + symbol as u32, 8),
+        144..=255 => writer.write_huffman_code(0b110010000 + (symbol - 144) as u32, 9),
+        256..=279 => writer.write_huffman_code((symbol - 256) as u32, 7),
+        280..=287 => writer.write_huffman_code(0b11000000 + (symbol - 280) as u32, 8),
+        _ => panic!("Invalid literal/length symbol: {}", symbol),
+    }
+}
+
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    bit_buffer: u32,
+    bit_count: u32,
+}
+
+impl BitWriter {
+    /// Writes the lowest `count` bits of `value`, least-significant bit first.
+    fn write_bits(&mut self, value: u32, count: u32) {
+        for i in 0..count {
+            self.bit_buffer |= ((value >> i) & 1) << self.bit_count;
+            self.bit_count += 1;
+
+            if self.bit_count == 8 {
+                self.bytes.push(self.bit_buffer as u8);
+                self.bit_buffer = 0;
+                self.bit_count = 0;
+            }
+        }
+    }
+
+    /// Writes a `length`-bit Huffman code, which (unlike everything else) is packed
+    /// most-significant bit first.
+    fn write_huffman_code(&mut self, code: u32, length: u32) {
+        self.write_bits(code.reverse_bits() >> (u32::BITS - length), length);
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.bit_count > 0 {
+            self.bytes.push(self.bit_buffer as u8);
+        }
+
+        self.bytes
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::RngCore;
+    use crate::deflate::{deflate, lz77, Token};
+
+    #[test]
+    fn test_lz77() {
+        assert_eq!(
+            vec![
+                Token::Literal(b'a'),
+                Token::Literal(b'b'),
+                Token::Literal(b'c'),
+                Token::Match { length: 7, distance: 3 },
+                Token::Literal(b'd'),
+            ],
+            lz77(b"abcabcabcad")
+        );
+    }
+
+    #[test]
+    fn test_deflate() {
+        let random_bytes = {
+            let mut random_bytes = vec![0; 4096];
+            rand::thread_rng().fill_bytes(&mut random_bytes);
+
+            random_bytes
+        };
+
+        let repetitive_bytes = b"All work and no play makes Jack a dull boy. ".repeat(100);
+
+        for bytes in [&[][..], b"a", &random_bytes, &repetitive_bytes] {
+            let compressed = deflate(bytes);
+            assert_eq!(bytes, miniz_oxide::inflate::decompress_to_vec(&compressed).unwrap());
+        }
+
+        assert!(deflate(&repetitive_bytes).len() < repetitive_bytes.len() / 10);
+    }
+}
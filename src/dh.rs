@@ -0,0 +1,246 @@
+use lazy_static::lazy_static;
+use rand::Rng;
+use crate::bigint::BigUint;
+use crate::sha1::Sha1;
+
+// The primes are all safe primes with 2 as a generator of their order-q subgroup, where
+// q = (p - 1) / 2.
+
+// RFC 3526 groups 5 and 14 (the 1536-bit one is the NIST prime from challenge 33)
+const MODP_1536_PRIME: &str = "\
+    ffffffffffffffffc90fdaa22168c234c4c6628b80dc1cd129024e088a67cc74
+    020bbea63b139b22514a08798e3404ddef9519b3cd3a431b302b0a6df25f1437
+    4fe1356d6d51c245e485b576625e7ec6f44c42e9a637ed6b0bff5cb6f406b7ed
+    ee386bfb5a899fa5ae9f24117c4b1fe649286651ece45b3dc2007cb8a163bf05
+    98da48361c55d39a69163fa8fd24cf5f83655d23dca3ad961c62f356208552bb
+    9ed529077096966d670c354e4abc9804f1746c08ca237327ffffffffffffffff";
+
+const MODP_2048_PRIME: &str = "\
+    ffffffffffffffffc90fdaa22168c234c4c6628b80dc1cd129024e088a67cc74
+    020bbea63b139b22514a08798e3404ddef9519b3cd3a431b302b0a6df25f1437
+    4fe1356d6d51c245e485b576625e7ec6f44c42e9a637ed6b0bff5cb6f406b7ed
+    ee386bfb5a899fa5ae9f24117c4b1fe649286651ece45b3dc2007cb8a163bf05
+    98da48361c55d39a69163fa8fd24cf5f83655d23dca3ad961c62f356208552bb
+    9ed529077096966d670c354e4abc9804f1746c08ca18217c32905e462e36ce3b
+    e39e772c180e86039b2783a2ec07a28fb5c55df06f4c52c9de2bcbf695581718
+    3995497cea956ae515d2261898fa051015728e5a8aacaa68ffffffffffffffff";
+
+// RFC 7919 groups (ffdhe2048, ffdhe3072 and ffdhe4096)
+const FFDHE_2048_PRIME: &str = "\
+    ffffffffffffffffadf85458a2bb4a9aafdc5620273d3cf1d8b9c583ce2d3695
+    a9e13641146433fbcc939dce249b3ef97d2fe363630c75d8f681b202aec4617a
+    d3df1ed5d5fd65612433f51f5f066ed0856365553ded1af3b557135e7f57c935
+    984f0c70e0e68b77e2a689daf3efe8721df158a136ade73530acca4f483a797a
+    bc0ab182b324fb61d108a94bb2c8e3fbb96adab760d7f4681d4f42a3de394df4
+    ae56ede76372bb190b07a7c8ee0a6d709e02fce1cdf7e2ecc03404cd28342f61
+    9172fe9ce98583ff8e4f1232eef28183c3fe3b1b4c6fad733bb5fcbc2ec22005
+    c58ef1837d1683b2c6f34a26c1b2effa886b423861285c97ffffffffffffffff";
+
+const FFDHE_3072_PRIME: &str = "\
+    ffffffffffffffffadf85458a2bb4a9aafdc5620273d3cf1d8b9c583ce2d3695
+    a9e13641146433fbcc939dce249b3ef97d2fe363630c75d8f681b202aec4617a
+    d3df1ed5d5fd65612433f51f5f066ed0856365553ded1af3b557135e7f57c935
+    984f0c70e0e68b77e2a689daf3efe8721df158a136ade73530acca4f483a797a
+    bc0ab182b324fb61d108a94bb2c8e3fbb96adab760d7f4681d4f42a3de394df4
+    ae56ede76372bb190b07a7c8ee0a6d709e02fce1cdf7e2ecc03404cd28342f61
+    9172fe9ce98583ff8e4f1232eef28183c3fe3b1b4c6fad733bb5fcbc2ec22005
+    c58ef1837d1683b2c6f34a26c1b2effa886b4238611fcfdcde355b3b6519035b
+    bc34f4def99c023861b46fc9d6e6c9077ad91d2691f7f7ee598cb0fac186d91c
+    aefe130985139270b4130c93bc437944f4fd4452e2d74dd364f2e21e71f54bff
+    5cae82ab9c9df69ee86d2bc522363a0dabc521979b0deada1dbf9a42d5c4484e
+    0abcd06bfa53ddef3c1b20ee3fd59d7c25e41d2b66c62e37ffffffffffffffff";
+
+const FFDHE_4096_PRIME: &str = "\
+    ffffffffffffffffadf85458a2bb4a9aafdc5620273d3cf1d8b9c583ce2d3695
+    a9e13641146433fbcc939dce249b3ef97d2fe363630c75d8f681b202aec4617a
+    d3df1ed5d5fd65612433f51f5f066ed0856365553ded1af3b557135e7f57c935
+    984f0c70e0e68b77e2a689daf3efe8721df158a136ade73530acca4f483a797a
+    bc0ab182b324fb61d108a94bb2c8e3fbb96adab760d7f4681d4f42a3de394df4
+    ae56ede76372bb190b07a7c8ee0a6d709e02fce1cdf7e2ecc03404cd28342f61
+    9172fe9ce98583ff8e4f1232eef28183c3fe3b1b4c6fad733bb5fcbc2ec22005
+    c58ef1837d1683b2c6f34a26c1b2effa886b4238611fcfdcde355b3b6519035b
+    bc34f4def99c023861b46fc9d6e6c9077ad91d2691f7f7ee598cb0fac186d91c
+    aefe130985139270b4130c93bc437944f4fd4452e2d74dd364f2e21e71f54bff
+    5cae82ab9c9df69ee86d2bc522363a0dabc521979b0deada1dbf9a42d5c4484e
+    0abcd06bfa53ddef3c1b20ee3fd59d7c25e41d2b669e1ef16e6f52c3164df4fb
+    7930e9e4e58857b6ac7d5f42d69f6d187763cf1d5503400487f55ba57e31cc7a
+    7135c886efb4318aed6a1e012d9e6832a907600a918130c46dc778f971ad0038
+    092999a333cb8b7a1a1db93d7140003c2a4ecea9f98d0acc0a8291cdcec97dcf
+    8ec9b55a7f88a46b4db5a851f44182e1c68a007e5e655f6affffffffffffffff";
+
+lazy_static! {
+    static ref MODP_1536: DhGroup = DhGroup::from_safe_prime(MODP_1536_PRIME);
+    static ref MODP_2048: DhGroup = DhGroup::from_safe_prime(MODP_2048_PRIME);
+    static ref FFDHE_2048: DhGroup = DhGroup::from_safe_prime(FFDHE_2048_PRIME);
+    static ref FFDHE_3072: DhGroup = DhGroup::from_safe_prime(FFDHE_3072_PRIME);
+    static ref FFDHE_4096: DhGroup = DhGroup::from_safe_prime(FFDHE_4096_PRIME);
+}
+
+/// Finite-field Diffie-Hellman parameters: a prime modulus `p` and a generator `g` of a subgroup
+/// of order `q`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DhGroup {
+    pub p: BigUint,
+    pub g: BigUint,
+    pub q: BigUint,
+}
+
+/// A private exponent and the matching public key g^x mod p.
+#[derive(Clone, Debug)]
+pub struct DhKeypair {
+    private_key: BigUint,
+    public_key: BigUint,
+}
+
+impl DhGroup {
+    pub fn new(p: BigUint, g: BigUint, q: BigUint) -> Self {
+        Self { p, g, q }
+    }
+
+    fn from_safe_prime(hex: &str) -> Self {
+        let p = BigUint::from_hex(hex).unwrap();
+        let q = &p >> 1;
+
+        Self::new(p, BigUint::from(2u8), q)
+    }
+
+    /// The 1536-bit MODP group from RFC 3526, whose prime is the one cryptopals calls the NIST
+    /// prime.
+    pub fn modp_1536() -> &'static Self {
+        &MODP_1536
+    }
+
+    /// The 2048-bit MODP group from RFC 3526.
+    pub fn modp_2048() -> &'static Self {
+        &MODP_2048
+    }
+
+    /// The 2048-bit group from RFC 7919.
+    pub fn ffdhe_2048() -> &'static Self {
+        &FFDHE_2048
+    }
+
+    /// The 3072-bit group from RFC 7919.
+    pub fn ffdhe_3072() -> &'static Self {
+        &FFDHE_3072
+    }
+
+    /// The 4096-bit group from RFC 7919.
+    pub fn ffdhe_4096() -> &'static Self {
+        &FFDHE_4096
+    }
+
+    /// Generates a keypair with a private exponent chosen uniformly from [1, q).
+    pub fn generate_keypair(&self, rng: &mut impl Rng) -> DhKeypair {
+        let private_key = BigUint::random_below(rng, &(&self.q - BigUint::one())) + BigUint::one();
+
+        self.keypair_from_private_key(private_key)
+    }
+
+    pub fn keypair_from_private_key(&self, private_key: BigUint) -> DhKeypair {
+        DhKeypair {
+            public_key: self.g.modpow(&private_key, &self.p),
+            private_key,
+        }
+    }
+
+    /// Checks that a public key is in the subgroup generated by `g`, and isn't one of the
+    /// degenerate values 0, 1 or p - 1 that force the shared secret.
+    pub fn is_valid_public_key(&self, public_key: &BigUint) -> bool {
+        public_key > &BigUint::one()
+            && public_key < &(&self.p - BigUint::one())
+            && public_key.modpow(&self.q, &self.p).is_one()
+    }
+}
+
+impl DhKeypair {
+    pub fn private_key(&self) -> &BigUint {
+        &self.private_key
+    }
+
+    pub fn public_key(&self) -> &BigUint {
+        &self.public_key
+    }
+
+    /// Returns the shared secret (the other party's public key raised to our private key). This
+    /// doesn't validate the other public key; see [DhGroup::is_valid_public_key].
+    pub fn shared_secret(&self, group: &DhGroup, other_public_key: &BigUint) -> BigUint {
+        other_public_key.modpow(&self.private_key, &group.p)
+    }
+}
+
+/// Derives an AES-128 key from a shared secret as cryptopals does: the first 16 bytes of the SHA-1
+/// hash of the secret's big-endian bytes.
+pub fn derive_aes_key(shared_secret: &BigUint) -> [u8; 16] {
+    let mut sha1 = Sha1::default();
+    sha1.update(&shared_secret.to_bytes_be());
+
+    sha1.finish()[..16].try_into().unwrap()
+}
+
+#[cfg(test)]
+mod test {
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+    use crate::aes::{aes_cbc_decrypt, aes_cbc_encrypt};
+    use crate::bigint::BigUint;
+    use crate::dh::{derive_aes_key, DhGroup};
+    use crate::pkcs7::pkcs7_strip;
+
+    #[test]
+    fn test_small_group() {
+        let group = DhGroup::new(BigUint::from(37u8), BigUint::from(5u8), BigUint::from(36u8));
+        let mut rng = StdRng::seed_from_u64(33);
+
+        for _ in 0..100 {
+            let alice = group.generate_keypair(&mut rng);
+            let bob = group.generate_keypair(&mut rng);
+
+            assert!(alice.private_key() < &group.q && !alice.private_key().is_zero());
+            assert_eq!(alice.shared_secret(&group, bob.public_key()), bob.shared_secret(&group, alice.public_key()));
+        }
+    }
+
+    #[test]
+    fn test_groups() {
+        for (group, bits) in [
+            (DhGroup::modp_1536(), 1536),
+            (DhGroup::modp_2048(), 2048),
+            (DhGroup::ffdhe_2048(), 2048),
+            (DhGroup::ffdhe_3072(), 3072),
+            (DhGroup::ffdhe_4096(), 4096),
+        ] {
+            assert_eq!(bits, group.p.bits());
+
+            // Both fixed ends of the RFC 3526 and RFC 7919 constructions
+            assert_eq!(Some(u64::MAX), (&group.p >> (bits - 64) as usize).to_u64());
+            assert_eq!(Some(u64::MAX), (&group.p % (BigUint::one() << 64)).to_u64());
+            assert!(group.is_valid_public_key(&group.g));
+        }
+
+        assert!(DhGroup::modp_1536().p.to_string().starts_with("24103124269210325885520760221975"));
+        assert!(DhGroup::ffdhe_2048().p.to_string().starts_with("32317006071311007300153513477825"));
+    }
+
+    #[test]
+    fn test_key_agreement() {
+        let group = DhGroup::modp_1536();
+        let mut rng = StdRng::seed_from_u64(1536);
+
+        let alice = group.generate_keypair(&mut rng);
+        let bob = group.generate_keypair(&mut rng);
+
+        assert!(group.is_valid_public_key(alice.public_key()));
+        assert!(!group.is_valid_public_key(&BigUint::one()));
+        assert!(!group.is_valid_public_key(&(&group.p - BigUint::one())));
+
+        let secret = alice.shared_secret(group, bob.public_key());
+        assert_eq!(secret, bob.shared_secret(group, alice.public_key()));
+
+        let key = derive_aes_key(&secret);
+        let iv = [0; 16];
+        let ciphertext = aes_cbc_encrypt(b"Attack at dawn", &key, &iv);
+
+        assert_eq!(b"Attack at dawn".as_slice(), pkcs7_strip(&aes_cbc_decrypt(&ciphertext, &derive_aes_key(&secret), &iv)));
+    }
+}
@@ -1,29 +1,143 @@
 use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+use crate::bigint::{BigInt, BigUint};
 
-#[derive(Debug)]
+/// An exact rational number. Fractions are always kept in lowest terms with a positive
+/// denominator, so equal values are structurally equal.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
 pub struct Fraction {
-    numerator: u64,
-    denominator: u64,
+    numerator: BigInt,
+    denominator: BigInt,
 }
 
 impl Fraction {
-    pub fn new(numerator: u64, denominator: u64) -> Self {
-        Fraction { numerator, denominator }
+    pub fn new(numerator: impl Into<BigInt>, denominator: impl Into<BigInt>) -> Self {
+        let numerator = numerator.into();
+        let denominator = denominator.into();
+
+        assert!(!denominator.is_zero(), "Denominator must not be zero");
+
+        let gcd = BigInt::from(numerator.gcd(&denominator));
+        let (numerator, denominator) = (numerator / &gcd, denominator / &gcd);
+
+        if denominator.is_negative() {
+            Self { numerator: -numerator, denominator: -denominator }
+        } else {
+            Self { numerator, denominator }
+        }
+    }
+
+    pub fn zero() -> Self {
+        Self::from(BigInt::zero())
+    }
+
+    pub fn numerator(&self) -> &BigInt {
+        &self.numerator
+    }
+
+    pub fn denominator(&self) -> &BigInt {
+        &self.denominator
+    }
+
+    pub fn is_integer(&self) -> bool {
+        self.denominator == BigInt::one()
+    }
+
+    pub fn abs(&self) -> Self {
+        Self {
+            numerator: self.numerator.abs(),
+            denominator: self.denominator.clone(),
+        }
+    }
+
+    pub fn recip(&self) -> Self {
+        Self::new(self.denominator.clone(), self.numerator.clone())
+    }
+
+    /// Returns the nearest f64, even when the numerator and denominator are too large to convert
+    /// on their own.
+    pub fn to_f64(&self) -> f64 {
+        let numerator = self.numerator.magnitude();
+        let denominator = self.denominator.magnitude();
+
+        // Scale the quotient to 65 significant bits (plus a sticky bit for anything left over), so
+        // converting it rounds correctly
+        let scale = 65 + denominator.bits() as i64 - numerator.bits() as i64;
+
+        let (quotient, remainder) = if scale >= 0 {
+            (numerator << scale as usize).div_rem(denominator)
+        } else {
+            numerator.div_rem(&(denominator << -scale as usize))
+        };
+
+        let quotient = (&quotient << 1) + BigUint::from(!remainder.is_zero() as u8);
+        let magnitude = scale_by_power_of_two(quotient.to_f64(), -(scale + 1));
+
+        if self.numerator.is_negative() {
+            -magnitude
+        } else {
+            magnitude
+        }
+    }
+
+    /// Returns the greatest integer less than or equal to this fraction.
+    pub fn floor(&self) -> BigInt {
+        self.numerator.div_rem_floor(&self.denominator).0
+    }
+
+    /// Returns the least integer greater than or equal to this fraction.
+    pub fn ceil(&self) -> BigInt {
+        -(-self).floor()
+    }
+
+    /// Returns the nearest integer, rounding half-way cases away from zero (like [f64::round]).
+    pub fn round(&self) -> BigInt {
+        let half = Fraction::new(1, 2);
+
+        if self.numerator.is_negative() {
+            -(-self + half).floor()
+        } else {
+            (self + &half).floor()
+        }
+    }
+}
+
+// Multiplies by 2^exponent in steps, since 2^exponent alone may not be representable even when
+// the product is
+fn scale_by_power_of_two(mut value: f64, mut exponent: i64) -> f64 {
+    while exponent != 0 && value != 0.0 && value.is_finite() {
+        let step = exponent.clamp(-1000, 1000);
+
+        value *= 2f64.powi(step as i32);
+        exponent -= step;
+    }
+
+    value
+}
+
+impl From<BigInt> for Fraction {
+    fn from(value: BigInt) -> Self {
+        Self { numerator: value, denominator: BigInt::one() }
     }
 }
 
-impl Eq for Fraction {
+impl From<BigUint> for Fraction {
+    fn from(value: BigUint) -> Self {
+        Self::from(BigInt::from(value))
+    }
 }
 
-impl PartialEq<Self> for Fraction {
-    fn eq(&self, other: &Self) -> bool {
-        self.numerator * other.denominator == other.numerator * self.denominator
+impl From<i64> for Fraction {
+    fn from(value: i64) -> Self {
+        Self::from(BigInt::from(value))
     }
 }
 
 impl Ord for Fraction {
     fn cmp(&self, other: &Self) -> Ordering {
-        (self.numerator * other.denominator).cmp(&(other.numerator * self.denominator))
+        // Denominators are positive, so cross-multiplying doesn't flip the comparison
+        (&self.numerator * &other.denominator).cmp(&(&other.numerator * &self.denominator))
     }
 }
 
@@ -33,19 +147,172 @@ impl PartialOrd<Self> for Fraction {
     }
 }
 
+impl Neg for &Fraction {
+    type Output = Fraction;
+
+    fn neg(self) -> Fraction {
+        Fraction {
+            numerator: -&self.numerator,
+            denominator: self.denominator.clone(),
+        }
+    }
+}
+
+impl Neg for Fraction {
+    type Output = Fraction;
+
+    fn neg(self) -> Fraction {
+        -&self
+    }
+}
+
+impl Add<&Fraction> for &Fraction {
+    type Output = Fraction;
+
+    fn add(self, other: &Fraction) -> Fraction {
+        Fraction::new(
+            &self.numerator * &other.denominator + &other.numerator * &self.denominator,
+            &self.denominator * &other.denominator,
+        )
+    }
+}
+
+impl Sub<&Fraction> for &Fraction {
+    type Output = Fraction;
+
+    fn sub(self, other: &Fraction) -> Fraction {
+        self + &-other
+    }
+}
+
+impl Mul<&Fraction> for &Fraction {
+    type Output = Fraction;
+
+    fn mul(self, other: &Fraction) -> Fraction {
+        Fraction::new(&self.numerator * &other.numerator, &self.denominator * &other.denominator)
+    }
+}
+
+impl Div<&Fraction> for &Fraction {
+    type Output = Fraction;
+
+    fn div(self, other: &Fraction) -> Fraction {
+        assert!(!other.numerator.is_zero(), "Division by zero");
+
+        Fraction::new(&self.numerator * &other.denominator, &self.denominator * &other.numerator)
+    }
+}
+
+// Implements the owned variants of a binary operator in terms of the by-reference one
+macro_rules! forward_binary_operator {
+    ($operator:ident, $method:ident) => {
+        impl $operator<Fraction> for Fraction {
+            type Output = Fraction;
+
+            fn $method(self, other: Fraction) -> Fraction {
+                (&self).$method(&other)
+            }
+        }
+
+        impl $operator<&Fraction> for Fraction {
+            type Output = Fraction;
+
+            fn $method(self, other: &Fraction) -> Fraction {
+                (&self).$method(other)
+            }
+        }
+
+        impl $operator<Fraction> for &Fraction {
+            type Output = Fraction;
+
+            fn $method(self, other: Fraction) -> Fraction {
+                self.$method(&other)
+            }
+        }
+    };
+}
+
+forward_binary_operator!(Add, add);
+forward_binary_operator!(Sub, sub);
+forward_binary_operator!(Mul, mul);
+forward_binary_operator!(Div, div);
+
+impl fmt::Display for Fraction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_integer() {
+            write!(f, "{}", self.numerator)
+        } else {
+            write!(f, "{}/{}", self.numerator, self.denominator)
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
+    use crate::bigint::BigInt;
     use crate::fraction::Fraction;
 
     #[test]
     fn test_eq() {
         assert_eq!(Fraction::new(1, 2), Fraction::new(84, 168));
         assert_ne!(Fraction::new(1, 2), Fraction::new(84, 169));
+        assert_eq!(Fraction::new(-1, 2), Fraction::new(3, -6));
     }
 
     #[test]
     fn test_cmp() {
         assert!(Fraction::new(1, 2) < Fraction::new(2, 3));
         assert!(Fraction::new(1, 3) > Fraction::new(9, 30));
+        assert!(Fraction::new(-1, 2) < Fraction::new(-1, 3));
+
+        // These cross-multiply to more than 64 bits
+        assert!(Fraction::new(u64::MAX - 1, u64::MAX) < Fraction::new(u64::MAX, u64::MAX - 1));
+        assert!(Fraction::new(u64::MAX - 1, u64::MAX) < Fraction::new(1, 1));
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let a = Fraction::new(1, 6);
+        let b = Fraction::new(-3, 4);
+
+        assert_eq!(Fraction::new(-7, 12), &a + &b);
+        assert_eq!(Fraction::new(11, 12), &a - &b);
+        assert_eq!(Fraction::new(-1, 8), &a * &b);
+        assert_eq!(Fraction::new(-2, 9), &a / &b);
+        assert_eq!(Fraction::new(-4, 3), b.recip());
+        assert_eq!(Fraction::zero(), &a - &a);
+
+        assert_eq!("-7/12", (&a + &b).to_string());
+        assert_eq!("5", Fraction::new(10, 2).to_string());
+    }
+
+    #[test]
+    fn test_rounding() {
+        for (numerator, denominator, floor, ceil, round) in [
+            (7, 2, 3, 4, 4),
+            (-7, 2, -4, -3, -4),
+            (5, 3, 1, 2, 2),
+            (-5, 3, -2, -1, -2),
+            (4, 3, 1, 2, 1),
+            (6, 3, 2, 2, 2),
+            (0, 5, 0, 0, 0),
+        ] {
+            let fraction = Fraction::new(numerator, denominator);
+
+            assert_eq!(BigInt::from(floor), fraction.floor());
+            assert_eq!(BigInt::from(ceil), fraction.ceil());
+            assert_eq!(BigInt::from(round), fraction.round());
+            assert_eq!(numerator as f64 / denominator as f64, fraction.to_f64());
+        }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_to_f64() {
+        let huge = BigInt::from(10).pow(400);
+
+        assert_eq!(1.0 / 3.0, Fraction::new(&huge * BigInt::from(1), &huge * BigInt::from(3)).to_f64());
+        assert_eq!(-0.1, Fraction::new(-1, 10).to_f64());
+        assert_eq!(1e-300, Fraction::new(1, BigInt::from(10).pow(300)).to_f64());
+        assert_eq!(f64::INFINITY, Fraction::from(huge).to_f64());
+    }
+}
@@ -0,0 +1,222 @@
+use std::ops::BitXorAssign;
+
+/// A fixed-length vector over GF(2), packed into 64-bit words.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BitVector {
+    words: Vec<u64>,
+    len: usize,
+}
+
+impl BitVector {
+    pub fn zero(len: usize) -> Self {
+        Self {
+            words: vec![0; len.div_ceil(64)],
+            len,
+        }
+    }
+
+    pub fn unit(len: usize, i: usize) -> Self {
+        let mut vector = Self::zero(len);
+        vector.set(i, true);
+
+        vector
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn get(&self, i: usize) -> bool {
+        assert!(i < self.len);
+        self.words[i / 64] & (1 << (i % 64)) != 0
+    }
+
+    pub fn set(&mut self, i: usize, value: bool) {
+        assert!(i < self.len);
+
+        if value {
+            self.words[i / 64] |= 1 << (i % 64);
+        } else {
+            self.words[i / 64] &= !(1 << (i % 64));
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.words.iter().all(|&word| word == 0)
+    }
+
+    /// Returns the index of the lowest set bit, if any.
+    pub fn first_set(&self) -> Option<usize> {
+        self.words.iter()
+            .position(|&word| word != 0)
+            .map(|i| i * 64 + self.words[i].trailing_zeros() as usize)
+    }
+
+    /// Returns the inner product of this vector and another (i.e. the parity of the bits they
+    /// have in common).
+    pub fn dot(&self, other: &BitVector) -> bool {
+        assert_eq!(self.len, other.len);
+
+        self.words.iter()
+            .zip(other.words.iter())
+            .map(|(a, b)| (a & b).count_ones())
+            .sum::<u32>() % 2 == 1
+    }
+
+    fn xor_from_word(&mut self, other: &BitVector, start_word: usize) {
+        self.words[start_word..].iter_mut()
+            .zip(other.words[start_word..].iter())
+            .for_each(|(a, b)| *a ^= b);
+    }
+}
+
+impl BitXorAssign<&BitVector> for BitVector {
+    fn bitxor_assign(&mut self, other: &BitVector) {
+        assert_eq!(self.len, other.len);
+        self.xor_from_word(other, 0);
+    }
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct InconsistentEquation;
+
+/// A system of linear equations over GF(2), kept in row echelon form as equations are added.
+/// Each stored equation's pivot is its lowest variable, so equations never have to be revisited
+/// once they've been added.
+pub struct LinearSystem {
+    variables: usize,
+    pivots: Vec<Option<(BitVector, bool)>>,
+    rank: usize,
+}
+
+impl LinearSystem {
+    pub fn new(variables: usize) -> Self {
+        Self {
+            variables,
+            pivots: vec![None; variables],
+            rank: 0,
+        }
+    }
+
+    /// Adds the equation `coefficients · x = value` to the system. Returns `true` if the equation
+    /// was independent of those already in the system, `false` if it was redundant, or an error
+    /// if it contradicts the existing equations.
+    pub fn add_equation(&mut self, mut coefficients: BitVector, mut value: bool) -> Result<bool, InconsistentEquation> {
+        assert_eq!(self.variables, coefficients.len());
+
+        let mut word_index = 0;
+
+        while word_index < coefficients.words.len() {
+            if coefficients.words[word_index] == 0 {
+                word_index += 1;
+                continue;
+            }
+
+            let pivot = word_index * 64 + coefficients.words[word_index].trailing_zeros() as usize;
+
+            if let Some((pivot_coefficients, pivot_value)) = &self.pivots[pivot] {
+                // Everything below the pivot is already zero in both equations
+                coefficients.xor_from_word(pivot_coefficients, word_index);
+                value ^= pivot_value;
+            } else {
+                self.pivots[pivot] = Some((coefficients, value));
+                self.rank += 1;
+
+                return Ok(true);
+            }
+        }
+
+        if value {
+            Err(InconsistentEquation)
+        } else {
+            Ok(false)
+        }
+    }
+
+    pub fn rank(&self) -> usize {
+        self.rank
+    }
+
+    /// Returns true if the given variable has a pivot (i.e. it isn't a free variable).
+    pub fn is_determined(&self, variable: usize) -> bool {
+        self.pivots[variable].is_some()
+    }
+
+    /// Returns a solution to the system, with any free variables set to zero.
+    pub fn solve(&self) -> BitVector {
+        let mut solution = BitVector::zero(self.variables);
+
+        // Work backward from the highest pivot so that every variable after the pivot in a given
+        // equation is already known
+        for (pivot, equation) in self.pivots.iter().enumerate().rev() {
+            if let Some((coefficients, value)) = equation {
+                solution.set(pivot, value ^ coefficients.dot(&solution));
+            }
+        }
+
+        solution
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::Rng;
+    use crate::gf2::{BitVector, InconsistentEquation, LinearSystem};
+
+    #[test]
+    fn test_bit_vector() {
+        let mut vector = BitVector::zero(130);
+
+        assert!(vector.is_zero());
+        assert_eq!(None, vector.first_set());
+
+        vector.set(129, true);
+        vector.set(70, true);
+
+        assert!(vector.get(70));
+        assert!(!vector.get(71));
+        assert_eq!(Some(70), vector.first_set());
+
+        vector ^= &BitVector::unit(130, 70);
+
+        assert_eq!(Some(129), vector.first_set());
+        assert!(vector.dot(&BitVector::unit(130, 129)));
+        assert!(!vector.dot(&BitVector::unit(130, 0)));
+    }
+
+    #[test]
+    fn test_solve() {
+        const VARIABLES: usize = 200;
+
+        let mut expected = BitVector::zero(VARIABLES);
+
+        for i in 0..VARIABLES {
+            expected.set(i, rand::thread_rng().gen());
+        }
+
+        let mut system = LinearSystem::new(VARIABLES);
+
+        while system.rank() < VARIABLES {
+            let mut coefficients = BitVector::zero(VARIABLES);
+
+            for i in 0..VARIABLES {
+                coefficients.set(i, rand::thread_rng().gen_bool(0.125));
+            }
+
+            let value = coefficients.dot(&expected);
+            system.add_equation(coefficients, value).unwrap();
+        }
+
+        assert_eq!(expected, system.solve());
+
+        // Anything else we add should be redundant, and contradictions should be caught
+        let coefficients = BitVector::unit(VARIABLES, 17);
+
+        assert_eq!(Ok(false), system.add_equation(coefficients.clone(), expected.get(17)));
+        assert_eq!(Err(InconsistentEquation), system.add_equation(coefficients, !expected.get(17)));
+    }
+}
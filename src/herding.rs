@@ -0,0 +1,261 @@
+use std::collections::{HashMap, HashSet};
+use std::io;
+use std::io::{ErrorKind, Read, Write};
+use rand::RngCore;
+use crate::cheap_hash::{BLOCK_SIZE, CheapHash};
+
+/// A "diamond structure" for a [CheapHash] with an `N`-byte state: 2^k leaf states that collapse
+/// pairwise, level by level, into a single root state. Any message that reaches one of the leaves
+/// can be extended with k more blocks to reach the root.
+pub struct Diamond<const N: usize> {
+    // states[0] holds the 2^k leaves, and the last level holds only the root
+    states: Vec<Vec<[u8; N]>>,
+
+    // blocks[level][i] takes states[level][i] to states[level + 1][i / 2]
+    blocks: Vec<Vec<[u8; BLOCK_SIZE]>>,
+}
+
+impl<const N: usize> Diamond<N> {
+    pub fn build(k: usize) -> Self {
+        let leaves = {
+            let mut leaves = HashSet::with_capacity(1 << k);
+
+            while leaves.len() < 1 << k {
+                let mut leaf = [0; N];
+                rand::thread_rng().fill_bytes(&mut leaf);
+
+                leaves.insert(leaf);
+            }
+
+            leaves.into_iter().collect()
+        };
+
+        let mut states: Vec<Vec<[u8; N]>> = vec![leaves];
+        let mut blocks = Vec::with_capacity(k);
+
+        while states[states.len() - 1].len() > 1 {
+            let mut level_blocks = Vec::with_capacity(states[states.len() - 1].len());
+            let mut next_states = Vec::with_capacity(states[states.len() - 1].len() / 2);
+
+            for pair in states[states.len() - 1].chunks_exact(2) {
+                let (block_a, block_b, state) = Self::find_collision(&pair[0], &pair[1]);
+
+                level_blocks.push(block_a);
+                level_blocks.push(block_b);
+                next_states.push(state);
+            }
+
+            blocks.push(level_blocks);
+            states.push(next_states);
+        }
+
+        Self { states, blocks }
+    }
+
+    fn find_collision(a: &[u8; N], b: &[u8; N]) -> ([u8; BLOCK_SIZE], [u8; BLOCK_SIZE], [u8; N]) {
+        // Rather than searching for a block that takes `a` to some fixed state, grow a table of
+        // outputs from each starting state and wait for the birthday paradox to do its thing
+        let mut blocks_from_a = HashMap::new();
+        let mut blocks_from_b = HashMap::new();
+
+        loop {
+            let block_a = random_block();
+            let state = CheapHash::compress(a, &block_a);
+
+            if let Some(block_b) = blocks_from_b.get(&state) {
+                return (block_a, *block_b, state);
+            }
+
+            blocks_from_a.insert(state, block_a);
+
+            let block_b = random_block();
+            let state = CheapHash::compress(b, &block_b);
+
+            if let Some(block_a) = blocks_from_a.get(&state) {
+                return (*block_a, block_b, state);
+            }
+
+            blocks_from_b.insert(state, block_b);
+        }
+    }
+
+    pub fn depth(&self) -> usize {
+        self.blocks.len()
+    }
+
+    pub fn root(&self) -> [u8; N] {
+        self.states[self.states.len() - 1][0]
+    }
+
+    /// Returns the hash that [Diamond::herd] will produce for any prefix that occupies exactly
+    /// `prefix_blocks` blocks. This is the value to commit to before the prefix is known.
+    pub fn prediction(&self, prefix_blocks: usize) -> [u8; N] {
+        let message_length_bytes = (prefix_blocks + 1 + self.depth()) * BLOCK_SIZE;
+
+        CheapHash::<N>::with_initial_state(&self.root(), message_length_bytes).finish()
+    }
+
+    /// Builds a message that begins with the given prefix (zero-padded to a block boundary) and
+    /// hashes to the value returned by [Diamond::prediction] for the prefix's length in blocks.
+    pub fn herd(&self, prefix: &[u8]) -> Vec<u8> {
+        let mut message = Vec::from(prefix);
+        message.resize(prefix.len().div_ceil(BLOCK_SIZE) * BLOCK_SIZE, 0);
+
+        let prefix_state = {
+            let mut hash = CheapHash::<N>::default();
+            hash.update(&message);
+            hash.state()
+        };
+
+        let leaf_indices: HashMap<[u8; N], usize> = self.states[0].iter()
+            .enumerate()
+            .map(|(i, leaf)| (*leaf, i))
+            .collect();
+
+        let (linking_block, mut index) = loop {
+            let block = random_block();
+
+            if let Some(&index) = leaf_indices.get(&CheapHash::compress(&prefix_state, &block)) {
+                break (block, index);
+            }
+        };
+
+        message.extend_from_slice(&linking_block);
+
+        for level_blocks in &self.blocks {
+            message.extend_from_slice(&level_blocks[index]);
+            index /= 2;
+        }
+
+        message
+    }
+
+    pub fn write_to(&self, writer: &mut impl Write) -> io::Result<()> {
+        let state_size = u8::try_from(N).map_err(|_| io::Error::new(ErrorKind::InvalidInput, "State size is too large"))?;
+        let depth = u8::try_from(self.depth()).map_err(|_| io::Error::new(ErrorKind::InvalidInput, "Diamond is too deep"))?;
+
+        writer.write_all(&[state_size, depth])?;
+
+        for leaf in &self.states[0] {
+            writer.write_all(leaf)?;
+        }
+
+        for block in self.blocks.iter().flatten() {
+            writer.write_all(block)?;
+        }
+
+        Ok(())
+    }
+
+    /// Reads a diamond written by [Diamond::write_to]. Intermediate states aren't stored, so
+    /// they're recomputed (and checked for consistency) as the diamond is read.
+    pub fn read_from(reader: &mut impl Read) -> io::Result<Self> {
+        let mut header = [0; 2];
+        reader.read_exact(&mut header)?;
+
+        let [state_size, k] = header;
+
+        if state_size as usize != N {
+            return Err(io::Error::new(ErrorKind::InvalidData, "Unexpected state size"));
+        }
+
+        // The header isn't trusted, so the leaves aren't preallocated: a bogus depth runs into the
+        // end of the stream rather than a huge allocation
+        if k as u32 >= usize::BITS {
+            return Err(io::Error::new(ErrorKind::InvalidData, "Diamond is too deep"));
+        }
+
+        let mut states = vec![vec![]];
+
+        for _ in 0..1usize << k {
+            let mut leaf = [0; N];
+            reader.read_exact(&mut leaf)?;
+
+            states[0].push(leaf);
+        }
+
+        let mut blocks = Vec::with_capacity(k as usize);
+
+        for level in 0..k as usize {
+            let mut level_blocks = Vec::with_capacity(states[level].len());
+            let mut next_states = Vec::with_capacity(states[level].len() / 2);
+
+            for pair in states[level].chunks_exact(2) {
+                let mut block_a = [0; BLOCK_SIZE];
+                let mut block_b = [0; BLOCK_SIZE];
+
+                reader.read_exact(&mut block_a)?;
+                reader.read_exact(&mut block_b)?;
+
+                let state = CheapHash::compress(&pair[0], &block_a);
+
+                if state != CheapHash::compress(&pair[1], &block_b) {
+                    return Err(io::Error::new(ErrorKind::InvalidData, "Blocks do not collide"));
+                }
+
+                level_blocks.push(block_a);
+                level_blocks.push(block_b);
+                next_states.push(state);
+            }
+
+            blocks.push(level_blocks);
+            states.push(next_states);
+        }
+
+        Ok(Self { states, blocks })
+    }
+}
+
+fn random_block() -> [u8; BLOCK_SIZE] {
+    let mut block = [0; BLOCK_SIZE];
+    rand::thread_rng().fill_bytes(&mut block);
+
+    block
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::ErrorKind;
+    use crate::cheap_hash::CheapHash;
+    use crate::herding::Diamond;
+
+    #[test]
+    fn test_herd() {
+        let diamond = Diamond::<2>::build(5);
+        let prediction = diamond.prediction(2);
+
+        let prefix = b"Spring training scores: ";
+        let message = diamond.herd(prefix);
+
+        assert!(message.starts_with(prefix));
+
+        let mut hash = CheapHash::<2>::default();
+        hash.update(&message);
+
+        assert_eq!(prediction, hash.finish());
+    }
+
+    #[test]
+    fn test_write_read() {
+        let diamond = Diamond::<2>::build(4);
+
+        let mut serialized = vec![];
+        diamond.write_to(&mut serialized).unwrap();
+
+        let deserialized = Diamond::<2>::read_from(&mut serialized.as_slice()).unwrap();
+
+        assert_eq!(diamond.states, deserialized.states);
+        assert_eq!(diamond.blocks, deserialized.blocks);
+
+        serialized[2] ^= 0x01;
+        assert!(Diamond::<2>::read_from(&mut serialized.as_slice()).is_err());
+
+        assert!(Diamond::<3>::read_from(&mut serialized.as_slice()).is_err());
+
+        // Absurd depths in the header fail cleanly instead of overflowing or allocating
+        for depth in [40, 64, 255] {
+            let error = Diamond::<2>::read_from(&mut [2, depth, 0, 0].as_slice()).err().unwrap();
+            assert!(matches!(error.kind(), ErrorKind::UnexpectedEof | ErrorKind::InvalidData));
+        }
+    }
+}
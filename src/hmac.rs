@@ -0,0 +1,94 @@
+use crate::sha1::Sha1;
+
+const BLOCK_SIZE: usize = 64;
+
+/// HMAC (RFC 2104) with SHA-1 as the hash function.
+pub struct HmacSha1 {
+    inner: Sha1,
+    outer_key: [u8; BLOCK_SIZE],
+}
+
+impl HmacSha1 {
+    pub fn new(key: &[u8]) -> Self {
+        // Keys longer than a block are hashed first; shorter ones are zero-padded
+        let mut block_key = [0; BLOCK_SIZE];
+
+        if key.len() > BLOCK_SIZE {
+            let mut sha1 = Sha1::default();
+            sha1.update(key);
+
+            let hash = sha1.finish();
+            block_key[..hash.len()].copy_from_slice(&hash);
+        } else {
+            block_key[..key.len()].copy_from_slice(key);
+        }
+
+        let mut inner = Sha1::default();
+        inner.update(&block_key.map(|b| b ^ 0x36));
+
+        Self {
+            inner,
+            outer_key: block_key.map(|b| b ^ 0x5c),
+        }
+    }
+
+    pub fn update(&mut self, bytes: &[u8]) {
+        self.inner.update(bytes);
+    }
+
+    pub fn finish(self) -> Vec<u8> {
+        let mut outer = Sha1::default();
+        outer.update(&self.outer_key);
+        outer.update(&self.inner.finish());
+
+        outer.finish()
+    }
+}
+
+pub fn hmac_sha1(key: &[u8], message: &[u8]) -> Vec<u8> {
+    let mut hmac = HmacSha1::new(key);
+    hmac.update(message);
+
+    hmac.finish()
+}
+
+/// Checks a MAC without returning early at the first mismatched byte, so the time taken doesn't
+/// reveal how much of it was right.
+pub fn verify_hmac_sha1(key: &[u8], message: &[u8], mac: &[u8]) -> bool {
+    let expected = hmac_sha1(key, message);
+
+    expected.len() == mac.len() && expected.iter().zip(mac).fold(0, |acc, (a, b)| acc | (a ^ b)) == 0
+}
+
+#[cfg(test)]
+mod test {
+    use crate::hmac::{hmac_sha1, verify_hmac_sha1, HmacSha1};
+
+    #[test]
+    fn test_hmac_sha1() {
+        // Test vectors from RFC 2202
+        for (key, message, mac) in [
+            (vec![0x0b; 20], b"Hi There".to_vec(), "b617318655057264e28bc0b6fb378c8ef146be00"),
+            (b"Jefe".to_vec(), b"what do ya want for nothing?".to_vec(), "effcdf6ae5eb2fa2d27416d5f184df9c259a7c79"),
+            (vec![0xaa; 20], vec![0xdd; 50], "125d7342b9ac11cd91a39af48aa17b4f63f175d3"),
+            (vec![0xaa; 80], b"Test Using Larger Than Block-Size Key - Hash Key First".to_vec(), "aa4ae5e15272d00e95705637ce8a3b55ed402112"),
+            (
+                vec![0xaa; 80],
+                b"Test Using Larger Than Block-Size Key and Larger Than One Block-Size Data".to_vec(),
+                "e8e99d0f45237d786d6bbaa7965c7808bbff1a91",
+            ),
+        ] {
+            assert_eq!(hex::decode(mac).unwrap(), hmac_sha1(&key, &message));
+            assert!(verify_hmac_sha1(&key, &message, &hex::decode(mac).unwrap()));
+        }
+
+        let mut hmac = HmacSha1::new(b"Jefe");
+        hmac.update(b"what do ya want ");
+        hmac.update(b"for nothing?");
+
+        assert_eq!(hex::decode("effcdf6ae5eb2fa2d27416d5f184df9c259a7c79").unwrap(), hmac.finish());
+
+        assert!(!verify_hmac_sha1(b"Jefe", b"what do ya want for nothing?", &[0; 20]));
+        assert!(!verify_hmac_sha1(b"Jefe", b"what do ya want for nothing?", &[]));
+    }
+}
@@ -1,7 +1,23 @@
 pub mod aes;
+pub mod bigint;
+pub mod cheap_hash;
+pub mod crime;
+pub mod deflate;
+pub mod dh;
 pub mod fraction;
+pub mod gf2;
+pub mod herding;
+pub mod hmac;
 pub mod md4;
+pub mod mitm;
+pub mod mt_cipher;
 pub mod pkcs7;
+pub mod primes;
 pub mod random;
+pub mod rc4;
+pub mod rsa;
+pub mod seed_search;
 pub mod sha1;
+pub mod srp;
 pub mod text;
+pub mod xor;
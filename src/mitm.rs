@@ -0,0 +1,424 @@
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Mutex;
+use std::thread;
+use rand::RngCore;
+use crate::aes::{aes_cbc_decrypt, aes_cbc_encrypt};
+use crate::bigint::BigUint;
+use crate::dh::{derive_aes_key, DhGroup};
+use crate::text::{self, TextScorer};
+
+const BLOCK_SIZE: usize = 16;
+
+/// The messages of a toy protocol in which Alice and Bob agree on a group, exchange Diffie-Hellman
+/// public keys and then trade encrypted messages (Bob echoes everything Alice sends him):
+///
+/// ```text
+/// A -> B: Negotiate { p, g }
+/// B -> A: Ack
+/// A -> B: PublicKey(A)
+/// B -> A: PublicKey(B)
+/// A -> B: Encrypted(message)
+/// B -> A: Encrypted(message)
+/// ...
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Message {
+    Negotiate { p: BigUint, g: BigUint },
+    Ack,
+    PublicKey(BigUint),
+
+    /// An AES-128-CBC ciphertext followed by its IV
+    Encrypted(Vec<u8>),
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Direction {
+    AliceToBob,
+    BobToAlice,
+}
+
+/// One end of a simulated network connection.
+pub struct Endpoint {
+    sender: Sender<Message>,
+    receiver: Receiver<Message>,
+}
+
+/// Returns both ends of a new connection.
+pub fn connect() -> (Endpoint, Endpoint) {
+    let (a_sender, b_receiver) = mpsc::channel();
+    let (b_sender, a_receiver) = mpsc::channel();
+
+    (
+        Endpoint { sender: a_sender, receiver: a_receiver },
+        Endpoint { sender: b_sender, receiver: b_receiver },
+    )
+}
+
+impl Endpoint {
+    pub fn send(&self, message: Message) {
+        self.sender.send(message).expect("Connection closed");
+    }
+
+    /// Returns the next message, or `None` once the other end has hung up.
+    pub fn receive(&self) -> Option<Message> {
+        self.receiver.recv().ok()
+    }
+
+    fn expect_receive(&self) -> Message {
+        self.receive().expect("Connection closed")
+    }
+}
+
+/// Something sitting between Alice and Bob that sees (and may rewrite) every message.
+pub trait Interceptor {
+    fn intercept(&mut self, direction: Direction, message: Message) -> Message;
+}
+
+/// Encrypts a message under the given key with a random IV, and appends the IV.
+pub fn encrypt_message(key: &[u8; 16], plaintext: &[u8]) -> Vec<u8> {
+    let mut iv = [0; BLOCK_SIZE];
+    rand::thread_rng().fill_bytes(&mut iv);
+
+    // aes_cbc_encrypt only pads a final partial block, so add the full block of padding that
+    // PKCS#7 calls for when there isn't one
+    let mut plaintext = plaintext.to_vec();
+
+    if plaintext.len().is_multiple_of(BLOCK_SIZE) {
+        plaintext.extend_from_slice(&[BLOCK_SIZE as u8; BLOCK_SIZE]);
+    }
+
+    let mut message = aes_cbc_encrypt(&plaintext, key, &iv);
+    message.extend_from_slice(&iv);
+
+    message
+}
+
+/// Decrypts a message from [encrypt_message], or returns `None` if its padding is invalid.
+pub fn decrypt_message(key: &[u8; 16], message: &[u8]) -> Option<Vec<u8>> {
+    if message.len() < 2 * BLOCK_SIZE || !message.len().is_multiple_of(BLOCK_SIZE) {
+        return None;
+    }
+
+    let (ciphertext, iv) = message.split_at(message.len() - BLOCK_SIZE);
+    let mut plaintext = aes_cbc_decrypt(ciphertext, key, iv);
+
+    let padding = *plaintext.last()? as usize;
+
+    if !(1..=BLOCK_SIZE).contains(&padding) || plaintext[plaintext.len() - padding..].iter().any(|&b| b as usize != padding) {
+        return None;
+    }
+
+    plaintext.truncate(plaintext.len() - padding);
+
+    Some(plaintext)
+}
+
+/// Plays Alice: proposes the group, exchanges keys, then sends each message and waits for Bob to
+/// echo it. Returns the echoes she decrypted (`None` where decryption failed).
+pub fn run_alice(endpoint: &Endpoint, group: &DhGroup, messages: &[&[u8]]) -> Vec<Option<Vec<u8>>> {
+    endpoint.send(Message::Negotiate { p: group.p.clone(), g: group.g.clone() });
+
+    let Message::Ack = endpoint.expect_receive() else {
+        panic!("Expected an acknowledgement");
+    };
+
+    let keypair = group.generate_keypair(&mut rand::thread_rng());
+    endpoint.send(Message::PublicKey(keypair.public_key().clone()));
+
+    let Message::PublicKey(bob_public_key) = endpoint.expect_receive() else {
+        panic!("Expected a public key");
+    };
+
+    let key = derive_aes_key(&keypair.shared_secret(group, &bob_public_key));
+
+    messages.iter()
+        .map(|message| {
+            endpoint.send(Message::Encrypted(encrypt_message(&key, message)));
+
+            let Message::Encrypted(echo) = endpoint.expect_receive() else {
+                panic!("Expected an encrypted message");
+            };
+
+            decrypt_message(&key, &echo)
+        })
+        .collect()
+}
+
+/// Plays Bob: accepts whatever group Alice proposes, exchanges keys, and then echoes every message
+/// until Alice hangs up. Messages he can't decrypt are echoed as empty messages.
+pub fn run_bob(endpoint: &Endpoint) {
+    let Message::Negotiate { p, g } = endpoint.expect_receive() else {
+        panic!("Expected group parameters");
+    };
+
+    endpoint.send(Message::Ack);
+
+    let Message::PublicKey(alice_public_key) = endpoint.expect_receive() else {
+        panic!("Expected a public key");
+    };
+
+    // Bob doesn't know the subgroup order, so the exponent is just taken from [1, p - 1)
+    let group = DhGroup::new(p.clone(), g, p - BigUint::one());
+    let keypair = group.generate_keypair(&mut rand::thread_rng());
+
+    endpoint.send(Message::PublicKey(keypair.public_key().clone()));
+
+    let key = derive_aes_key(&keypair.shared_secret(&group, &alice_public_key));
+
+    while let Some(message) = endpoint.receive() {
+        let Message::Encrypted(message) = message else {
+            panic!("Expected an encrypted message");
+        };
+
+        let plaintext = decrypt_message(&key, &message).unwrap_or_default();
+        endpoint.send(Message::Encrypted(encrypt_message(&key, &plaintext)));
+    }
+}
+
+/// Forwards messages between Alice's and Bob's connections in both directions, passing each one
+/// through the interceptor on the way, until both sides have hung up.
+pub fn relay(alice: Endpoint, bob: Endpoint, interceptor: &mut (impl Interceptor + Send)) {
+    let interceptor = Mutex::new(interceptor);
+
+    thread::scope(|scope| {
+        let forward = |from: Receiver<Message>, to: Sender<Message>, direction| {
+            let interceptor = &interceptor;
+
+            scope.spawn(move || {
+                for message in from {
+                    let message = interceptor.lock().unwrap().intercept(direction, message);
+
+                    if to.send(message).is_err() {
+                        break;
+                    }
+                }
+            });
+        };
+
+        forward(alice.receiver, bob.sender, Direction::AliceToBob);
+        forward(bob.receiver, alice.sender, Direction::BobToAlice);
+    });
+}
+
+/// Runs the protocol between Alice and Bob with the interceptor in the middle, and returns the
+/// echoes Alice decrypted.
+pub fn simulate(group: &DhGroup, messages: &[&[u8]], interceptor: &mut (impl Interceptor + Send)) -> Vec<Option<Vec<u8>>> {
+    let (alice, alice_remote) = connect();
+    let (bob_remote, bob) = connect();
+
+    thread::scope(|scope| {
+        scope.spawn(move || run_bob(&bob));
+        scope.spawn(move || relay(alice_remote, bob_remote, interceptor));
+
+        // Alice hangs up once she's done, which lets the relay and Bob finish before the scope
+        // waits for them
+        let echoes = run_alice(&alice, group, messages);
+        drop(alice);
+
+        echoes
+    })
+}
+
+/// A passive interceptor that just records every message.
+#[derive(Default)]
+pub struct Eavesdropper {
+    pub transcript: Vec<(Direction, Message)>,
+}
+
+impl Interceptor for Eavesdropper {
+    fn intercept(&mut self, direction: Direction, message: Message) -> Message {
+        self.transcript.push((direction, message.clone()));
+        message
+    }
+}
+
+/// The key-fixing attack: replaces both public keys with p, which forces both shared secrets to
+/// zero. Alice and Bob can still talk to each other, and Mallory can read everything.
+#[derive(Default)]
+pub struct KeyFixing {
+    p: Option<BigUint>,
+
+    /// Every decrypted message, in the order it was sent
+    pub recovered: Vec<(Direction, Vec<u8>)>,
+}
+
+impl Interceptor for KeyFixing {
+    fn intercept(&mut self, direction: Direction, message: Message) -> Message {
+        match message {
+            Message::Negotiate { ref p, .. } => {
+                self.p = Some(p.clone());
+                message
+            }
+            Message::PublicKey(_) => Message::PublicKey(self.p.clone().expect("Keys exchanged before negotiation")),
+            Message::Encrypted(ref ciphertext) => {
+                if let Some(plaintext) = decrypt_message(&derive_aes_key(&BigUint::zero()), ciphertext) {
+                    self.recovered.push((direction, plaintext));
+                }
+
+                message
+            }
+            Message::Ack => message,
+        }
+    }
+}
+
+/// The generators that make a negotiated group degenerate.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum MaliciousGenerator {
+    One,
+    P,
+    PMinusOne,
+}
+
+/// The parameter-injection attack: Bob is told to use a degenerate generator g', and Alice's
+/// public key is replaced with g' too. Bob's shared secret is then g'^b, which is also his public
+/// key, and Alice's is that raised to her private key, which can only be 0 or ±1. Mallory
+/// decrypts everything and re-encrypts it under the other side's key.
+pub struct ParameterInjection {
+    generator: MaliciousGenerator,
+    p: Option<BigUint>,
+    bob_key: Option<[u8; 16]>,
+    alice_key: Option<[u8; 16]>,
+
+    /// Every decrypted message, in the order it was sent
+    pub recovered: Vec<(Direction, Vec<u8>)>,
+}
+
+impl ParameterInjection {
+    pub fn new(generator: MaliciousGenerator) -> Self {
+        Self { generator, p: None, bob_key: None, alice_key: None, recovered: vec![] }
+    }
+
+    fn malicious_generator(&self) -> BigUint {
+        let p = self.p.as_ref().expect("Keys exchanged before negotiation");
+
+        match self.generator {
+            MaliciousGenerator::One => BigUint::one(),
+            MaliciousGenerator::P => p.clone(),
+            MaliciousGenerator::PMinusOne => p - BigUint::one(),
+        }
+    }
+
+    // Alice's secret is Bob's public key raised to her private key. If his key is p - 1, that's
+    // 1 or p - 1 depending on whether her key is even; the wrong key almost never yields valid
+    // padding, and never yields something that reads as well as the real message does.
+    fn alice_key(&mut self, ciphertext: &[u8]) -> Option<[u8; 16]> {
+        if self.alice_key.is_none() {
+            let p = self.p.as_ref().unwrap();
+            let scorer = text::english();
+
+            self.alice_key = [BigUint::one(), p - BigUint::one(), BigUint::zero()]
+                .iter()
+                .map(derive_aes_key)
+                .filter_map(|key| decrypt_message(&key, ciphertext).map(|plaintext| (key, scorer.score(&plaintext))))
+                .max_by(|(_, a), (_, b)| a.total_cmp(b))
+                .map(|(key, _)| key);
+        }
+
+        self.alice_key
+    }
+}
+
+impl Interceptor for ParameterInjection {
+    fn intercept(&mut self, direction: Direction, message: Message) -> Message {
+        match (direction, message) {
+            (_, Message::Negotiate { p, .. }) => {
+                self.p = Some(p.clone());
+                Message::Negotiate { p, g: self.malicious_generator() }
+            }
+            (Direction::AliceToBob, Message::PublicKey(_)) => Message::PublicKey(self.malicious_generator()),
+            (Direction::BobToAlice, Message::PublicKey(bob_public_key)) => {
+                // Bob's secret is (g')^b mod p, which is exactly the public key he just sent
+                self.bob_key = Some(derive_aes_key(&bob_public_key));
+                Message::PublicKey(bob_public_key)
+            }
+            (direction, Message::Encrypted(ciphertext)) => {
+                let bob_key = self.bob_key.expect("Messages sent before key exchange");
+
+                let (from_key, to_key) = match direction {
+                    Direction::AliceToBob => (self.alice_key(&ciphertext), Some(bob_key)),
+                    Direction::BobToAlice => (Some(bob_key), self.alice_key),
+                };
+
+                match (from_key.and_then(|key| decrypt_message(&key, &ciphertext)), to_key) {
+                    (Some(plaintext), Some(to_key)) => {
+                        let message = Message::Encrypted(encrypt_message(&to_key, &plaintext));
+                        self.recovered.push((direction, plaintext));
+                        message
+                    }
+                    _ => Message::Encrypted(ciphertext),
+                }
+            }
+            (_, message) => message,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::dh::DhGroup;
+    use crate::mitm::{decrypt_message, Direction, Eavesdropper, encrypt_message, KeyFixing, MaliciousGenerator, Message, ParameterInjection, simulate};
+
+    const MESSAGES: [&[u8]; 3] = [
+        b"Meet me at the usual place",
+        b"Bring the documents.",
+        b"Exactly sixteen!",
+    ];
+
+    #[test]
+    fn test_encrypt_message() {
+        let key = [7; 16];
+
+        for message in MESSAGES {
+            assert_eq!(Some(message.to_vec()), decrypt_message(&key, &encrypt_message(&key, message)));
+        }
+
+        assert_eq!(Some(vec![]), decrypt_message(&key, &encrypt_message(&key, b"")));
+        assert_eq!(None, decrypt_message(&key, b"too short"));
+    }
+
+    #[test]
+    fn test_honest_exchange() {
+        let mut eavesdropper = Eavesdropper::default();
+        let echoes = simulate(DhGroup::modp_1536(), &MESSAGES, &mut eavesdropper);
+
+        assert_eq!(MESSAGES.iter().map(|m| Some(m.to_vec())).collect::<Vec<_>>(), echoes);
+
+        assert_eq!(4 + 2 * MESSAGES.len(), eavesdropper.transcript.len());
+        assert!(matches!(eavesdropper.transcript[3], (Direction::BobToAlice, Message::PublicKey(_))));
+    }
+
+    #[test]
+    fn test_key_fixing() {
+        let mut mallory = KeyFixing::default();
+        let echoes = simulate(DhGroup::modp_1536(), &MESSAGES, &mut mallory);
+
+        assert_eq!(MESSAGES.iter().map(|m| Some(m.to_vec())).collect::<Vec<_>>(), echoes);
+        assert_eq!(
+            MESSAGES.iter()
+                .flat_map(|m| [(Direction::AliceToBob, m.to_vec()), (Direction::BobToAlice, m.to_vec())])
+                .collect::<Vec<_>>(),
+            mallory.recovered
+        );
+    }
+
+    #[test]
+    fn test_parameter_injection() {
+        for generator in [MaliciousGenerator::One, MaliciousGenerator::P, MaliciousGenerator::PMinusOne] {
+            // Run a few times, since Alice's key is only ambiguous when g' = p - 1 and her private
+            // key is odd
+            for _ in 0..4 {
+                let mut mallory = ParameterInjection::new(generator);
+                let echoes = simulate(DhGroup::modp_1536(), &MESSAGES, &mut mallory);
+
+                assert_eq!(MESSAGES.iter().map(|m| Some(m.to_vec())).collect::<Vec<_>>(), echoes, "{:?}", generator);
+                assert_eq!(
+                    MESSAGES.iter()
+                        .flat_map(|m| [(Direction::AliceToBob, m.to_vec()), (Direction::BobToAlice, m.to_vec())])
+                        .collect::<Vec<_>>(),
+                    mallory.recovered
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,189 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use crate::random::{MersenneTwister, Mt19937, TwisterParameters};
+use crate::seed_search::{parallel_search, SeedSpace};
+
+const WORD_SIZE: usize = 4;
+
+/// The order in which the bytes of each generator output are used as keystream.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ByteOrder {
+    BigEndian,
+    LittleEndian,
+}
+
+impl ByteOrder {
+    fn bytes(&self, word: u32) -> [u8; WORD_SIZE] {
+        match self {
+            ByteOrder::BigEndian => word.to_be_bytes(),
+            ByteOrder::LittleEndian => word.to_le_bytes(),
+        }
+    }
+}
+
+/// A stream cipher whose keystream is the output of a [MersenneTwister] seeded with the key.
+/// Keys are limited to `seed_bits` bits (e.g. 16 bits for challenge 24).
+#[derive(Copy, Clone, Debug)]
+pub struct MtStreamCipher {
+    seed_bits: u32,
+    byte_order: ByteOrder,
+}
+
+/// The outcome of a known-plaintext seed search.
+#[derive(Clone, Debug)]
+pub struct SeedRecovery {
+    /// Every seed that produces the known plaintext, from highest to lowest
+    pub seeds: Vec<u32>,
+
+    /// The number of candidate seeds searched
+    pub candidates: u64,
+
+    /// The number of candidates that survived the first comparison and had to be checked with a
+    /// fully-seeded generator
+    pub full_checks: u64,
+
+    pub elapsed: Duration,
+}
+
+impl MtStreamCipher {
+    pub fn new(seed_bits: u32, byte_order: ByteOrder) -> Self {
+        assert!((1..=u32::BITS).contains(&seed_bits));
+
+        Self { seed_bits, byte_order }
+    }
+
+    pub fn keystream(&self, seed: u32, length: usize) -> Vec<u8> {
+        assert!(seed <= self.max_seed(), "Seed is wider than {} bits", self.seed_bits);
+
+        let mut mt = MersenneTwister::new(seed);
+        let mut keystream = Vec::with_capacity(length.next_multiple_of(WORD_SIZE));
+
+        while keystream.len() < length {
+            keystream.extend_from_slice(&self.byte_order.bytes(mt.next_u32()));
+        }
+
+        keystream.truncate(length);
+        keystream
+    }
+
+    pub fn transform(&self, text: &[u8], seed: u32) -> Vec<u8> {
+        text.iter()
+            .zip(self.keystream(seed, text.len()))
+            .map(|(a, b)| a ^ b)
+            .collect()
+    }
+
+    /// Recovers the seeds that could have produced a ciphertext, given plaintext known to start
+    /// at `known_offset` within it.
+    ///
+    /// Candidates are checked one output at a time, starting from the first output that overlaps
+    /// the known plaintext, and rejected at the first mismatch. If that output is early enough in
+    /// the stream, it's computed with [MersenneTwister::nth_output], which rejects nearly every
+    /// candidate without seeding a whole generator.
+    pub fn recover_seed(&self, ciphertext: &[u8], known_plaintext: &[u8], known_offset: usize) -> SeedRecovery {
+        assert!(!known_plaintext.is_empty());
+        assert!(known_offset + known_plaintext.len() <= ciphertext.len());
+
+        let start = Instant::now();
+
+        // known_outputs[i] holds the known keystream bytes of output first_output + i
+        let first_output = known_offset / WORD_SIZE;
+        let mut known_outputs = vec![[None; WORD_SIZE]; (known_offset + known_plaintext.len()).div_ceil(WORD_SIZE) - first_output];
+
+        for (i, (c, p)) in ciphertext[known_offset..].iter().zip(known_plaintext.iter()).enumerate() {
+            let position = known_offset + i;
+            known_outputs[position / WORD_SIZE - first_output][position % WORD_SIZE] = Some(c ^ p);
+        }
+
+        let matches = |output: u32, known_bytes: &[Option<u8>; WORD_SIZE]| {
+            self.byte_order.bytes(output)
+                .iter()
+                .zip(known_bytes.iter())
+                .all(|(b, known)| known.is_none_or(|known| known == *b))
+        };
+
+        let full_checks = AtomicU64::new(0);
+
+        let seeds = parallel_search(
+            SeedSpace::Window { latest: self.max_seed(), before: self.max_seed() },
+            |seed| {
+                if first_output < Mt19937::N - Mt19937::M
+                    && !matches(MersenneTwister::nth_output(seed, first_output), &known_outputs[0])
+                {
+                    return false;
+                }
+
+                full_checks.fetch_add(1, Ordering::Relaxed);
+
+                let mut mt = MersenneTwister::new(seed);
+
+                for _ in 0..first_output {
+                    mt.next_u32();
+                }
+
+                known_outputs.iter().all(|known_bytes| matches(mt.next_u32(), known_bytes))
+            },
+        );
+
+        SeedRecovery {
+            seeds,
+            candidates: self.max_seed() as u64 + 1,
+            full_checks: full_checks.into_inner(),
+            elapsed: start.elapsed(),
+        }
+    }
+
+    fn max_seed(&self) -> u32 {
+        u32::MAX >> (u32::BITS - self.seed_bits)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::{Rng, RngCore};
+    use crate::mt_cipher::{ByteOrder, MtStreamCipher};
+
+    #[test]
+    fn test_transform() {
+        let cipher = MtStreamCipher::new(16, ByteOrder::BigEndian);
+        let seed = rand::thread_rng().gen_range(0..1 << 16);
+        let text = b"I'm pretty sure the two halves of this challenge are separate ideas.";
+
+        assert_ne!(text, cipher.transform(text, seed).as_slice());
+        assert_eq!(text, cipher.transform(&cipher.transform(text, seed), seed).as_slice());
+
+        let little_endian = MtStreamCipher::new(16, ByteOrder::LittleEndian);
+        let keystream = cipher.keystream(seed, 8);
+
+        assert_eq!(
+            [keystream[3], keystream[2], keystream[1], keystream[0], keystream[7], keystream[6]],
+            little_endian.keystream(seed, 6).as_slice()
+        );
+    }
+
+    #[test]
+    fn test_recover_seed() {
+        let known_plaintext = b"AAAAAAAAAAAAAA";
+
+        for (seed_bits, byte_order, prefix_length) in [
+            (18, ByteOrder::BigEndian, rand::thread_rng().gen_range(0..32)),
+            (16, ByteOrder::LittleEndian, rand::thread_rng().gen_range(0..32)),
+
+            // Too far into the stream to skip seeding a whole generator for each candidate
+            (12, ByteOrder::BigEndian, 1_000),
+        ] {
+            let cipher = MtStreamCipher::new(seed_bits, byte_order);
+            let seed = rand::thread_rng().gen_range(0..1 << seed_bits);
+
+            let mut plaintext = vec![0; prefix_length];
+            rand::thread_rng().fill_bytes(&mut plaintext);
+            plaintext.extend_from_slice(known_plaintext);
+
+            let ciphertext = cipher.transform(&plaintext, seed);
+            let recovery = cipher.recover_seed(&ciphertext, known_plaintext, prefix_length);
+
+            assert_eq!(vec![seed], recovery.seeds);
+            assert_eq!(1 << seed_bits, recovery.candidates);
+        }
+    }
+}
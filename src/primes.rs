@@ -0,0 +1,292 @@
+use lazy_static::lazy_static;
+use rand::Rng;
+use crate::bigint::{BigUint, Montgomery};
+
+// Candidates are checked against every prime below this before any Miller-Rabin round
+const SIEVE_LIMIT: usize = 2_000;
+
+// How far a sieved search goes from its random starting point before picking a new one
+const SEARCH_SPAN: u64 = 1 << 14;
+
+// A composite passes each round with a random base with probability at most 1/4
+const MILLER_RABIN_ROUNDS: usize = 32;
+
+// Testing against the first 13 primes (2 through 41) is deterministic below this bound (Sorenson
+// and Webster, "Strong pseudoprimes to twelve prime bases", 2015)
+const DETERMINISTIC_BOUND: u128 = 3_317_044_064_679_887_385_961_981;
+const DETERMINISTIC_BASES: [u64; 13] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41];
+
+lazy_static! {
+    pub static ref SMALL_PRIMES: Vec<u64> = sieve(SIEVE_LIMIT);
+}
+
+/// Returns every prime below `limit` (by the sieve of Eratosthenes).
+pub fn sieve(limit: usize) -> Vec<u64> {
+    let mut composite = vec![false; limit];
+
+    (2..limit)
+        .filter(|&n| {
+            if composite[n] {
+                return false;
+            }
+
+            for multiple in (n * n..limit).step_by(n) {
+                composite[multiple] = true;
+            }
+
+            true
+        })
+        .map(|n| n as u64)
+        .collect()
+}
+
+/// Tests whether `n` is prime. The answer is certain below about 2^81; above that, a composite
+/// has less than a 2^-64 chance of being reported as prime.
+pub fn is_prime(n: &BigUint) -> bool {
+    if let Some(n) = n.to_u64().filter(|&n| n < SIEVE_LIMIT as u64) {
+        return SMALL_PRIMES.binary_search(&n).is_ok();
+    }
+
+    if SMALL_PRIMES.iter().any(|&p| n.rem_u64(p) == 0) {
+        return false;
+    }
+
+    let tester = MillerRabin::new(n);
+
+    if n.to_u128().is_some_and(|n| n < DETERMINISTIC_BOUND) {
+        DETERMINISTIC_BASES.iter().all(|&base| tester.passes(&BigUint::from(base)))
+    } else {
+        // Bases are drawn from [2, n - 2]
+        let bound = n - BigUint::from(3u8);
+
+        (0..MILLER_RABIN_ROUNDS).all(|_| {
+            tester.passes(&(BigUint::random_below(&mut rand::thread_rng(), &bound) + BigUint::from(2u8)))
+        })
+    }
+}
+
+/// Returns whether an odd `n` greater than 2 is a strong probable prime to the given base, i.e.
+/// whether `base` fails to witness that `n` is composite.
+pub fn miller_rabin(n: &BigUint, base: &BigUint) -> bool {
+    MillerRabin::new(n).passes(base)
+}
+
+struct MillerRabin {
+    montgomery: Montgomery,
+    n_minus_one: BigUint,
+
+    // n - 1 = d * 2^s with d odd
+    d: BigUint,
+    s: u64,
+}
+
+impl MillerRabin {
+    fn new(n: &BigUint) -> Self {
+        assert!(n.is_odd() && n > &BigUint::from(2u8));
+
+        let n_minus_one = n - BigUint::one();
+        let s = (0..).find(|&i| n_minus_one.bit(i)).unwrap();
+
+        Self {
+            montgomery: Montgomery::new(n),
+            d: &n_minus_one >> s as usize,
+            n_minus_one,
+            s,
+        }
+    }
+
+    fn passes(&self, base: &BigUint) -> bool {
+        let n = self.montgomery.modulus();
+        let mut x = self.montgomery.pow(base, &self.d);
+
+        if x.is_one() || x == self.n_minus_one || x.is_zero() {
+            return true;
+        }
+
+        for _ in 1..self.s {
+            x = &x * &x % n;
+
+            if x == self.n_minus_one {
+                return true;
+            }
+        }
+
+        false
+    }
+}
+
+/// Returns a random prime with exactly `bits` bits. The top two bits are always set, so the
+/// product of two such primes has exactly `2 * bits` bits (as RSA moduli should).
+pub fn random_prime(rng: &mut impl Rng, bits: u64) -> BigUint {
+    assert!(bits >= 2);
+
+    if bits <= 32 {
+        // Too small to sieve without sieving out the primes themselves
+        let low = 3u64 << (bits - 2);
+
+        return loop {
+            let candidate = BigUint::from(rng.gen_range(low..1 << bits));
+
+            if is_prime(&candidate) {
+                break candidate;
+            }
+        };
+    }
+
+    sieved_search(rng, bits, |residue, _| residue == 0, is_prime)
+}
+
+/// Returns a random safe prime p = 2q + 1 (where q is also prime) with exactly `bits` bits, whose
+/// top two bits are set.
+pub fn random_safe_prime(rng: &mut impl Rng, bits: u64) -> BigUint {
+    assert!(bits >= 40, "Safe primes this small are better found by hand");
+
+    // Search over q, ruling out candidates where either q or 2q + 1 has a small factor
+    let q = sieved_search(
+        rng,
+        bits - 1,
+        |residue, p| residue == 0 || (2 * residue + 1) % p == 0,
+        |q| is_prime(&((q << 1) + BigUint::one())) && is_prime(q),
+    );
+
+    (q << 1) + BigUint::one()
+}
+
+/// Returns a random prime p = k * q + 1 with exactly `bits` bits, so that the multiplicative group
+/// mod p has a subgroup of order `q` (as in DSA, where q is a 160- or 256-bit prime).
+pub fn random_prime_with_factor(rng: &mut impl Rng, bits: u64, q: &BigUint) -> BigUint {
+    assert!(q.is_odd(), "q must be an odd prime");
+    assert!(bits > q.bits() + 1, "p must be larger than 2q");
+
+    // k ranges over the even numbers in [low, high], which put p in (2^(bits - 1), 2^bits)
+    let low = &(BigUint::one() << (bits - 1) as usize) / q + BigUint::one();
+    let high = &(BigUint::one() << bits as usize) / q;
+
+    let low = if low.is_odd() { low + BigUint::one() } else { low };
+    assert!(low <= high, "No even multiplier gives a number of this size");
+
+    let choices = ((&high - &low) >> 1) + BigUint::one();
+
+    loop {
+        let k = &low + (BigUint::random_below(rng, &choices) << 1);
+        let p = &k * q + BigUint::one();
+
+        if p.bits() == bits && is_prime(&p) {
+            return p;
+        }
+    }
+}
+
+// Searches upward from random odd starting points with the top two of `bits` bits set, skipping
+// every candidate whose residue r modulo any small prime p has `is_excluded(r, p)`, and returns
+// the first remaining candidate that passes `accept`.
+fn sieved_search(
+    rng: &mut impl Rng,
+    bits: u64,
+    is_excluded: impl Fn(u64, u64) -> bool,
+    accept: impl Fn(&BigUint) -> bool,
+) -> BigUint {
+    loop {
+        let start = BigUint::random_bits(rng, bits - 2) + (BigUint::from(3u8) << (bits - 2) as usize);
+        let start = if start.is_odd() { start } else { start + BigUint::one() };
+        let residues: Vec<u64> = SMALL_PRIMES.iter().map(|&p| start.rem_u64(p)).collect();
+
+        for offset in (0..SEARCH_SPAN).step_by(2) {
+            let sieved_out = SMALL_PRIMES.iter()
+                .zip(residues.iter())
+                .any(|(&p, &residue)| is_excluded((residue + offset) % p, p));
+
+            if sieved_out {
+                continue;
+            }
+
+            let candidate = &start + BigUint::from(offset);
+
+            if candidate.bits() != bits {
+                break;
+            }
+
+            if accept(&candidate) {
+                return candidate;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, Instant};
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+    use crate::bigint::BigUint;
+    use crate::primes::{is_prime, miller_rabin, random_prime, random_prime_with_factor, random_safe_prime, sieve};
+
+    #[test]
+    fn test_is_prime() {
+        let primes = sieve(10_000);
+
+        assert_eq!(1229, primes.len());
+
+        for n in 0..10_000u64 {
+            assert_eq!(primes.binary_search(&n).is_ok(), is_prime(&BigUint::from(n)), "{}", n);
+        }
+
+        // Mersenne primes and composites, and a strong pseudoprime to bases 2 through 37 (the
+        // smallest one, from Sorenson and Webster)
+        assert!(is_prime(&((BigUint::one() << 127) - BigUint::one())));
+        assert!(is_prime(&((BigUint::one() << 521) - BigUint::one())));
+        assert!(!is_prime(&((BigUint::one() << 128) - BigUint::one())));
+        assert!(!is_prime(&((BigUint::one() << 523) - BigUint::one())));
+
+        let pseudoprime: BigUint = "318665857834031151167461".parse().unwrap();
+
+        assert!([2u8, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37].iter().all(|&base| miller_rabin(&pseudoprime, &BigUint::from(base))));
+        assert!(!is_prime(&pseudoprime));
+
+        // 561 = 3 * 11 * 17 is a Carmichael number, but not a strong pseudoprime to base 2
+        assert!(!miller_rabin(&BigUint::from(561u16), &BigUint::from(2u8)));
+    }
+
+    #[test]
+    fn test_random_primes() {
+        let mut rng = StdRng::seed_from_u64(43);
+
+        for bits in [2, 5, 31, 33, 64, 100, 256] {
+            let p = random_prime(&mut rng, bits);
+
+            assert_eq!(bits, p.bits());
+            assert!(bits < 3 || p.bit(bits - 2));
+            assert!(is_prime(&p));
+        }
+
+        let p = random_safe_prime(&mut rng, 128);
+
+        assert_eq!(128, p.bits());
+        assert!(is_prime(&p) && is_prime(&(&p >> 1)));
+
+        let q = random_prime(&mut rng, 160);
+        let p = random_prime_with_factor(&mut rng, 512, &q);
+
+        assert_eq!(512, p.bits());
+        assert!(is_prime(&p));
+        assert!((&p - BigUint::one()) % &q == BigUint::zero());
+
+        // The only even k with 7k + 1 a 5-bit number is 4, which gives 29
+        assert_eq!(BigUint::from(29u8), random_prime_with_factor(&mut rng, 5, &BigUint::from(7u8)));
+    }
+
+    #[test]
+    fn test_rsa_sized_primes() {
+        // Generating a 2048-bit RSA key's primes takes about 0.3 seconds in release builds and about
+        // 4 seconds in debug builds; the bounds leave room for slower (or busier) machines
+        let limit = if cfg!(debug_assertions) { Duration::from_secs(30) } else { Duration::from_secs(2) };
+        let start = Instant::now();
+        let mut rng = StdRng::seed_from_u64(1024);
+
+        let p = random_prime(&mut rng, 1024);
+        let q = random_prime(&mut rng, 1024);
+
+        assert_eq!(2048, (&p * &q).bits());
+        assert!(start.elapsed() < limit, "Took {:?}", start.elapsed());
+    }
+}
@@ -1,83 +1,1213 @@
-const N: usize = 624;
-const M: usize = 397;
-const R: u32 = 31;
-const A: u32 = 0x9908b0df;
-const U: u8 = 11;
-const D: u32 = 0xffffffff;
-const S: u8 = 7;
-const B: u32 = 0x9d2c5680;
-const T: u8 = 15;
-const C: u32 = 0xefc60000;
-const L: u8 = 18;
-const F: u32 = 1812433253;
-
-const LOWER_MASK: u32 = (1 << R) - 1;
-const UPPER_MASK: u32 = !LOWER_MASK;
-
-pub struct MersenneTwister {
-    state: [u32; N],
+use std::fmt::Debug;
+use std::marker::PhantomData;
+use std::ops::{BitAnd, BitOr, BitXor, BitXorAssign, Not, Shl, Shr};
+use rand::{Error, RngCore, SeedableRng};
+use crate::gf2::{BitVector, LinearSystem};
+
+// Seed and multipliers for `init_by_array`; see http://www.math.sci.hiroshima-u.ac.jp/m-mat/MT/MT2002/CODES/mt19937ar.c
+const INIT_BY_ARRAY_SEED: u32 = 19650218;
+const INIT_BY_ARRAY_F1: u32 = 1664525;
+const INIT_BY_ARRAY_F2: u32 = 1566083941;
+
+/// An unsigned integer type that can serve as the word type of a [Twister].
+pub trait Word: Copy + Debug + Eq
+    + BitAnd<Output = Self> + BitOr<Output = Self> + BitXor<Output = Self> + BitXorAssign + Not<Output = Self>
+    + Shl<u32, Output = Self> + Shr<u32, Output = Self>
+{
+    const BITS: u32;
+    const ZERO: Self;
+    const ONE: Self;
+    const MAX: Self;
+
+    /// Converts an index into a word, truncating it if necessary.
+    fn from_index(i: usize) -> Self;
+
+    fn wrapping_add(self, other: Self) -> Self;
+    fn wrapping_sub(self, other: Self) -> Self;
+    fn wrapping_mul(self, other: Self) -> Self;
+}
+
+macro_rules! impl_word {
+    ($($t:ty),*) => {
+        $(
+            impl Word for $t {
+                const BITS: u32 = <$t>::BITS;
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+                const MAX: Self = <$t>::MAX;
+
+                fn from_index(i: usize) -> Self {
+                    i as $t
+                }
+
+                fn wrapping_add(self, other: Self) -> Self {
+                    <$t>::wrapping_add(self, other)
+                }
+
+                fn wrapping_sub(self, other: Self) -> Self {
+                    <$t>::wrapping_sub(self, other)
+                }
+
+                fn wrapping_mul(self, other: Self) -> Self {
+                    <$t>::wrapping_mul(self, other)
+                }
+            }
+        )*
+    };
+}
+
+impl_word!(u32, u64);
+
+/// The constants that define a member of the Mersenne Twister family, named as in
+/// https://en.wikipedia.org/wiki/Mersenne_Twister#Algorithmic_detail. These line up with the
+/// template parameters of C++'s `std::mersenne_twister_engine`, so any instantiation of that
+/// template can be reproduced here.
+pub trait TwisterParameters {
+    type Word: Word;
+
+    const N: usize;
+    const M: usize;
+
+    /// The number of bits taken from the lower word when each twist step splices two words
+    /// together
+    const R: u32;
+    const A: Self::Word;
+
+    const U: u32;
+    const D: Self::Word;
+    const S: u32;
+    const B: Self::Word;
+    const T: u32;
+    const C: Self::Word;
+    const L: u32;
+
+    /// The multiplier used to expand a single seed word into a full state
+    const F: Self::Word;
+}
+
+/// The original 32-bit MT19937, as used by C++'s `std::mt19937` and CPython's `random`.
+#[derive(Copy, Clone, Debug)]
+pub struct Mt19937;
+
+impl TwisterParameters for Mt19937 {
+    type Word = u32;
+
+    const N: usize = 624;
+    const M: usize = 397;
+    const R: u32 = 31;
+    const A: u32 = 0x9908b0df;
+    const U: u32 = 11;
+    const D: u32 = 0xffffffff;
+    const S: u32 = 7;
+    const B: u32 = 0x9d2c5680;
+    const T: u32 = 15;
+    const C: u32 = 0xefc60000;
+    const L: u32 = 18;
+    const F: u32 = 1812433253;
+}
+
+/// The 64-bit MT19937-64, as used by C++'s `std::mt19937_64`.
+#[derive(Copy, Clone, Debug)]
+pub struct Mt19937_64;
+
+impl TwisterParameters for Mt19937_64 {
+    type Word = u64;
+
+    const N: usize = 312;
+    const M: usize = 156;
+    const R: u32 = 31;
+    const A: u64 = 0xb5026f5aa96619e9;
+    const U: u32 = 29;
+    const D: u64 = 0x5555555555555555;
+    const S: u32 = 17;
+    const B: u64 = 0x71d67fffeda60000;
+    const T: u32 = 37;
+    const C: u64 = 0xfff7eee000000000;
+    const L: u32 = 43;
+    const F: u64 = 6364136223846793005;
+}
+
+/// A single bit observed in a generator's output stream.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct ObservedBit {
+    /// The position of the output within the observed stream (i.e. 0 for the first output seen)
+    pub output_index: usize,
+
+    /// The position of the bit within the output, where 0 is the least-significant bit
+    pub bit: u32,
+
+    pub value: bool,
+}
+
+impl ObservedBit {
+    /// Returns the bits of an output selected by the given mask; this is handy for outputs that
+    /// have been truncated (e.g. `output >> 24` leaks the bits in `0xff000000`).
+    pub fn from_masked_output(output_index: usize, output: u32, mask: u32) -> impl Iterator<Item = ObservedBit> {
+        (0..u32::BITS)
+            .filter(move |bit| mask & (1 << bit) != 0)
+            .map(move |bit| ObservedBit {
+                output_index,
+                bit,
+                value: output & (1 << bit) != 0,
+            })
+    }
+}
+
+/// A generator from the Mersenne Twister family with the given parameters.
+pub struct Twister<P: TwisterParameters> {
+    state: Vec<P::Word>,
+    index: usize,
+    parameters: PhantomData<P>,
+}
+
+pub type MersenneTwister = Twister<Mt19937>;
+pub type MersenneTwister64 = Twister<Mt19937_64>;
+
+impl<P: TwisterParameters> Twister<P> {
+    pub fn new(seed: P::Word) -> Self {
+        let mut state = vec![seed; P::N];
+
+        for i in 1..state.len() {
+            state[i] = Self::init_genrand_step(state[i - 1], i);
+        }
+
+        Self::from_state(state)
+    }
+
+    fn from_state(state: Vec<P::Word>) -> Self {
+        assert_eq!(P::N, state.len());
+
+        Self {
+            state,
+            index: P::N,
+            parameters: PhantomData,
+        }
+    }
+
+    pub fn next_word(&mut self) -> P::Word {
+        if self.index == P::N {
+            self.twist();
+        }
+
+        let y = Self::temper(self.state[self.index]);
+        self.index += 1;
+
+        y
+    }
+
+    /// Steps the generator backward, returning the output that preceded the next output. Calling
+    /// this immediately after [Twister::next_word] returns the same value again.
+    pub fn previous_word(&mut self) -> P::Word {
+        if self.index == 0 {
+            self.untwist();
+        }
+
+        self.index -= 1;
+
+        Self::temper(self.state[self.index])
+    }
+
+    /// Searches backward through at most `max_outputs` outputs for the point at which the
+    /// generator was seeded with [Twister::new], and returns the seed if found. This works just
+    /// as well on a generator cloned from observed outputs as on the original.
+    pub fn recover_seed(&self, max_outputs: usize) -> Option<P::Word> {
+        let mut rewound = self.clone();
+        let mut later_word = None;
+
+        for rewound_outputs in 1..=max_outputs {
+            rewound.previous_word();
+
+            let word = rewound.state[rewound.index];
+
+            // If this is the second word of a freshly-seeded state, then the next word should
+            // follow from it, and the seed (i.e. the first word) should precede it
+            if later_word == Some(Self::init_genrand_step(word, 2)) {
+                let seed = Self::invert_init_genrand_step(word, 1);
+
+                // Outputs start at word N + 1, so the word following the original's position
+                // (1 + rewound_outputs) tells us how far along the seeded generator should be
+                if let Some(skipped_outputs) = (1 + rewound_outputs).checked_sub(P::N) {
+                    let mut candidate = Self::new(seed);
+                    let mut original = self.clone();
+
+                    for _ in 0..skipped_outputs {
+                        candidate.next_word();
+                    }
+
+                    if (0..4).all(|_| candidate.next_word() == original.next_word()) {
+                        return Some(seed);
+                    }
+                }
+            }
+
+            later_word = Some(word);
+        }
+
+        None
+    }
+
+    /// Returns the first output of a generator seeded with [Twister::new] without building the rest
+    /// of its state; see [Twister::nth_output].
+    pub fn first_output(seed: P::Word) -> P::Word {
+        Self::nth_output(seed, 0)
+    }
+
+    /// Returns output `n` (counting from zero) of a generator seeded with [Twister::new] without
+    /// building the rest of its state. Twisted word n only depends on words n, n + 1, and n + M of
+    /// the seeded state, so this takes a fraction of the work of seeding a whole generator, which
+    /// adds up when searching for seeds. Outputs from N - M onward depend on words that have
+    /// already been twisted, so `n` must be less than N - M.
+    pub fn nth_output(seed: P::Word, n: usize) -> P::Word {
+        assert!(n < P::N - P::M);
+
+        let lower_mask = !(P::Word::MAX << P::R);
+
+        let mut word = seed;
+        let mut x = seed & !lower_mask;
+
+        for i in 1..=n + P::M {
+            word = Self::init_genrand_step(word, i);
+
+            if i == n {
+                x = word & !lower_mask;
+            } else if i == n + 1 {
+                x = x | (word & lower_mask);
+            }
+        }
+
+        Self::temper(word ^ Self::twist_word(x))
+    }
+
+    /// Builds a generator that continues the sequence from N consecutive outputs (624 for
+    /// MT19937) of another generator with the same parameters.
+    ///
+    /// The outputs don't need to line up with the other generator's twists. Each twist computes
+    /// every new state word from the words N, N - 1, and N - M positions before it, so any window
+    /// of N consecutive (untempered) outputs twists forward into the next N outputs just as well
+    /// as the window the other generator happens to be holding.
+    pub fn clone_from_outputs(outputs: &[P::Word]) -> Self {
+        Self::from_state(outputs.iter().map(|&output| Self::untemper(output)).collect())
+    }
+
+    fn twist(&mut self) {
+        let lower_mask = !(P::Word::MAX << P::R);
+        let upper_mask = !lower_mask;
+
+        for i in 0..self.state.len() {
+            let x = (self.state[i] & upper_mask) | (self.state[(i + 1) % P::N] & lower_mask);
+            self.state[i] = self.state[(i + P::M) % P::N] ^ Self::twist_word(x);
+        }
+
+        self.index = 0;
+    }
+
+    /// Applies the "mixing" half of a twist step (i.e. multiplication by the twist matrix).
+    fn twist_word(x: P::Word) -> P::Word {
+        if x & P::Word::ONE != P::Word::ZERO {
+            (x >> 1) ^ P::A
+        } else {
+            x >> 1
+        }
+    }
+
+    /// Restores the state block that [Twister::twist] replaced.
+    fn untwist(&mut self) {
+        let lower_mask = !(P::Word::MAX << P::R);
+        let upper_mask = !lower_mask;
+
+        // Undo the twist from the last word to the first. Each step recovers the upper bits of
+        // word i and the lower bits of word i + 1; words past i + M (mod N) have already been
+        // restored, while words before it still hold the twisted values the forward pass would
+        // have seen.
+        for i in (0..P::N).rev() {
+            let y = Self::untwist_word(self.state[i] ^ self.state[(i + P::M) % P::N]);
+
+            self.state[i] = (y & upper_mask) | (self.state[i] & lower_mask);
+
+            if i + 1 < P::N {
+                self.state[i + 1] = (self.state[i + 1] & upper_mask) | (y & lower_mask);
+            }
+        }
+
+        // The lower bits of the first word never make it into the next block, but the first word
+        // was itself produced by an earlier twist step alongside the words M - 1 and N - 1
+        let y = Self::untwist_word(self.state[P::N - 1] ^ self.state[P::M - 1]);
+        self.state[0] = (self.state[0] & upper_mask) | (y & lower_mask);
+
+        self.index = P::N;
+    }
+
+    /// Inverts [Twister::twist_word], `(y >> 1) ^ (if y is odd { A } else { 0 })`. The
+    /// top bit of `y >> 1` is always clear, so as long as the top bit of A is set (as it is for
+    /// both MT19937 and MT19937-64), the top bit of the result reveals whether A was applied.
+    fn untwist_word(mixed: P::Word) -> P::Word {
+        let top_bit = P::Word::ONE << (P::Word::BITS - 1);
+        assert!(P::A & top_bit != P::Word::ZERO, "Can't untwist without the top bit of A");
+
+        if mixed & top_bit != P::Word::ZERO {
+            ((mixed ^ P::A) << 1) | P::Word::ONE
+        } else {
+            mixed << 1
+        }
+    }
+
+    /// Computes the `i`th word of a freshly-seeded state from the word before it.
+    fn init_genrand_step(previous: P::Word, i: usize) -> P::Word {
+        P::F.wrapping_mul(previous ^ (previous >> (P::Word::BITS - 2))).wrapping_add(P::Word::from_index(i))
+    }
+
+    /// Recovers the word before the `i`th word of a freshly-seeded state.
+    fn invert_init_genrand_step(word: P::Word, i: usize) -> P::Word {
+        // F is odd, and so invertible modulo 2^w; Newton's method doubles the number of correct
+        // low bits with each step, starting from the three bits that F gets right on its own
+        let two = P::Word::from_index(2);
+        let mut f_inverse = P::F;
+
+        while P::F.wrapping_mul(f_inverse) != P::Word::ONE {
+            f_inverse = f_inverse.wrapping_mul(two.wrapping_sub(P::F.wrapping_mul(f_inverse)));
+        }
+
+        untemper_right_shift(
+            f_inverse.wrapping_mul(word.wrapping_sub(P::Word::from_index(i))),
+            P::Word::BITS - 2,
+            P::Word::MAX,
+        )
+    }
+
+    fn temper(x: P::Word) -> P::Word {
+        let mut y = x;
+        y ^= (y >> P::U) & P::D;
+        y ^= (y << P::S) & P::B;
+        y ^= (y << P::T) & P::C;
+        y ^= y >> P::L;
+
+        y
+    }
+
+    /// Inverts the tempering transformation applied to state words as they're returned from
+    /// [Twister::next_word], recovering the internal state word behind an output.
+    pub fn untemper(y: P::Word) -> P::Word {
+        let mut x = untemper_right_shift(y, P::L, P::Word::MAX);
+        x = untemper_left_shift(x, P::T, P::C);
+        x = untemper_left_shift(x, P::S, P::B);
+        untemper_right_shift(x, P::U, P::D)
+    }
+}
+
+impl MersenneTwister {
+    /// Seeds a generator from an array of words using `init_by_array` from the reference
+    /// implementation.
+    pub fn from_key(key: &[u32]) -> Self {
+        assert!(!key.is_empty());
+
+        const N: usize = Mt19937::N;
+
+        let mut mt = Self::new(INIT_BY_ARRAY_SEED);
+        let state = &mut mt.state;
+
+        let mut i = 1;
+        let mut j = 0;
+
+        for _ in 0..N.max(key.len()) {
+            state[i] = (state[i] ^ INIT_BY_ARRAY_F1.wrapping_mul(state[i - 1] ^ (state[i - 1] >> 30)))
+                .wrapping_add(key[j])
+                .wrapping_add(j as u32);
+
+            i += 1;
+            j += 1;
+
+            if i >= N {
+                state[0] = state[N - 1];
+                i = 1;
+            }
+
+            if j >= key.len() {
+                j = 0;
+            }
+        }
+
+        for _ in 0..N - 1 {
+            state[i] = (state[i] ^ INIT_BY_ARRAY_F2.wrapping_mul(state[i - 1] ^ (state[i - 1] >> 30)))
+                .wrapping_sub(i as u32);
+
+            i += 1;
+
+            if i >= N {
+                state[0] = state[N - 1];
+                i = 1;
+            }
+        }
+
+        // Guarantee a non-zero initial state
+        state[0] = 0x80000000;
+
+        mt
+    }
+
+    /// Seeds a generator the same way as CPython's `random.seed(n)` for a non-negative integer
+    /// `n`: the integer is split into 32-bit words (least-significant first) and passed to
+    /// `init_by_array`.
+    pub fn python_seeded(seed: u64) -> Self {
+        if seed >> u32::BITS == 0 {
+            Self::from_key(&[seed as u32])
+        } else {
+            Self::from_key(&[seed as u32, (seed >> u32::BITS) as u32])
+        }
+    }
+
+    pub fn next_u32(&mut self) -> u32 {
+        self.next_word()
+    }
+
+    pub fn previous_u32(&mut self) -> u32 {
+        self.previous_word()
+    }
+
+    /// Returns a float in [0, 1) the same way as CPython's `random.random()`, which builds a
+    /// 53-bit mantissa from the top 27 bits of one output and the top 26 bits of the next.
+    pub fn python_random(&mut self) -> f64 {
+        let a = self.next_u32() >> 5;
+        let b = self.next_u32() >> 6;
+
+        (a as f64 * 67108864.0 + b as f64) * (1.0 / 9007199254740992.0)
+    }
+
+    /// Returns `k` random bits the same way as CPython's `random.getrandbits(k)`. Bits are
+    /// returned as 32-bit words, least-significant word first; each word consumes one output, and
+    /// if `k` isn't a multiple of 32, the last word is truncated to its most-significant bits.
+    pub fn python_getrandbits(&mut self, k: usize) -> Vec<u32> {
+        let mut words = Vec::with_capacity(k.div_ceil(32));
+        let mut remaining_bits = k;
+
+        while remaining_bits > 0 {
+            let word = self.next_u32();
+
+            if remaining_bits < 32 {
+                words.push(word >> (32 - remaining_bits));
+                remaining_bits = 0;
+            } else {
+                words.push(word);
+                remaining_bits -= 32;
+            }
+        }
+
+        words
+    }
+
+    /// Returns a random integer in [0, n) the same way as CPython's `random._randbelow(n)`, which
+    /// backs `randrange`, `randint`, `choice`, and `shuffle`: draw `n.bit_length()` bits (the
+    /// bits of `n` itself, so a power of two draws one bit more than it needs) and reject anything
+    /// out of range.
+    pub fn python_randbelow(&mut self, n: u64) -> u64 {
+        assert!(n > 0);
+
+        let k = (u64::BITS - n.leading_zeros()) as usize;
+
+        loop {
+            let r = self.python_getrandbits(k)
+                .iter()
+                .enumerate()
+                .fold(0, |r, (i, &word)| r | (word as u64) << (32 * i));
+
+            if r < n {
+                return r;
+            }
+        }
+    }
+
+    /// Recovers a generator's state from an arbitrary collection of bits from its outputs, and
+    /// returns a generator whose next output follows the last observed output.
+    ///
+    /// Tempering and twisting are both linear over GF(2), so every output bit is the xor of some
+    /// subset of the 19,968 bits of state behind the first observed output. Running the generator
+    /// symbolically tells us which subset, and each observed bit then becomes one linear
+    /// equation. Roughly 20,000 independent equations (e.g. the top byte of ~2,500 outputs) are
+    /// enough to pin down the state. Returns `None` if the observations are contradictory or
+    /// don't determine the state.
+    pub fn clone_from_observed_bits(observations: &[ObservedBit]) -> Option<Self> {
+        const N: usize = Mt19937::N;
+        const STATE_BITS: usize = N * u32::BITS as usize;
+
+        let mut observations = observations.to_vec();
+        observations.sort_by_key(|observation| observation.output_index);
+
+        let last_output_index = observations.last()?.output_index;
+
+        // tempering_inputs[j] lists the state bits that get xor-ed together to produce bit j of
+        // a tempered output
+        let tempering_inputs: Vec<Vec<usize>> = (0..u32::BITS)
+            .map(|j| (0..u32::BITS as usize).filter(|&i| Self::temper(1 << i) & (1 << j) != 0).collect())
+            .collect();
+
+        // The symbolic state is a ring of N words, each of which is 32 bit vectors that express
+        // the word's bits in terms of the initial state bits. Slot `n % N` holds the word behind
+        // output n.
+        let mut symbolic_state: Vec<Vec<BitVector>> = (0..N)
+            .map(|word| {
+                (0..u32::BITS as usize)
+                    .map(|bit| BitVector::unit(STATE_BITS, word * u32::BITS as usize + bit))
+                    .collect()
+            })
+            .collect();
+
+        let mut system = LinearSystem::new(STATE_BITS);
+        let mut observations = observations.iter().peekable();
+
+        for output_index in 0..=last_output_index {
+            if output_index >= N {
+                symbolic_state[output_index % N] = Self::symbolic_twist(
+                    &symbolic_state[output_index % N],
+                    &symbolic_state[(output_index + 1) % N],
+                    &symbolic_state[(output_index + Mt19937::M) % N],
+                );
+            }
+
+            while let Some(observation) = observations.next_if(|o| o.output_index == output_index) {
+                let mut coefficients = BitVector::zero(STATE_BITS);
+
+                for &i in &tempering_inputs[observation.bit as usize] {
+                    coefficients ^= &symbolic_state[output_index % N][i];
+                }
+
+                system.add_equation(coefficients, observation.value).ok()?;
+            }
+        }
+
+        // The low 31 bits of the first word only ever affect the first output (the twist only
+        // uses the word's top bit), so we can live without those; everything else has to be
+        // pinned down.
+        if !(Mt19937::R as usize..STATE_BITS).all(|variable| system.is_determined(variable)) {
+            return None;
+        }
+
+        let solution = system.solve();
+        let mut state = vec![0; N];
+
+        for (i, word) in state.iter_mut().enumerate() {
+            for bit in 0..u32::BITS as usize {
+                if solution.get(i * u32::BITS as usize + bit) {
+                    *word |= 1 << bit;
+                }
+            }
+        }
+
+        let mut mt = Self::from_state(state);
+        mt.index = 0;
+
+        for _ in 0..=last_output_index {
+            mt.next_u32();
+        }
+
+        Some(mt)
+    }
+
+    /// Computes a single twisted word symbolically from the words 624, 623, and 227 positions
+    /// before it.
+    fn symbolic_twist(x_624: &[BitVector], x_623: &[BitVector], x_227: &[BitVector]) -> Vec<BitVector> {
+        let mut twisted = Vec::from(x_227);
+
+        // y = (x_624 & UPPER_MASK) | (x_623 & LOWER_MASK), and then the result is x_227 ^ (y >> 1),
+        // further xor-ed with A if the lowest bit of y is set
+        for (j, bit) in twisted.iter_mut().enumerate() {
+            if j + 1 < Mt19937::R as usize {
+                *bit ^= &x_623[j + 1];
+            } else if j + 1 == Mt19937::R as usize {
+                *bit ^= &x_624[j + 1];
+            }
+
+            if Mt19937::A & (1 << j) != 0 {
+                *bit ^= &x_623[0];
+            }
+        }
+
+        twisted
+    }
+}
+
+impl MersenneTwister64 {
+    pub fn next_u64(&mut self) -> u64 {
+        self.next_word()
+    }
+
+    pub fn previous_u64(&mut self) -> u64 {
+        self.previous_word()
+    }
+}
+
+impl<P: TwisterParameters> Clone for Twister<P> {
+    fn clone(&self) -> Self {
+        Self {
+            state: self.state.clone(),
+            index: self.index,
+            parameters: PhantomData,
+        }
+    }
+}
+
+fn untemper_right_shift<W: Word>(y: W, shift_magnitude: u32, mask: W) -> W {
+    // Right shifts take the form:
+    //
+    // y = x ^ ((x >> shift_magnitude) & mask)
+    //
+    // One really important observation here is that, if we're shifting a thing right, we know that
+    // the highest shift_magnitude bits in the result are all going to be zero, and if we're xor-ing
+    // that result with the original value, then the first shift_magnitude bits of THAT result
+    // are going to be the unchanged, original bits from the input value.
+    let mut x = y & W::MAX << (W::BITS - shift_magnitude);
+
+    // Then, for all of the lower bits, we know that:
+    //
+    //     y_N = (x_(N - shift_magnitude) & mask_N) ^ x_N
+    // ==> x_N = y_N ^ (x_(N - shift_magnitude) & mask_N)
+
+    let mut bit_mask = W::ONE << (W::BITS - shift_magnitude);
+
+    while bit_mask != W::ZERO {
+        x = x | ((y ^ ((x >> shift_magnitude) & mask)) & bit_mask);
+        bit_mask = bit_mask >> 1;
+    }
+
+    x
+}
+
+fn untemper_left_shift<W: Word>(y: W, shift_magnitude: u32, mask: W) -> W {
+    // Left shifts take the form:
+    //
+    // y = x ^ ((x << shift_magnitude) & mask)
+    //
+    // We can borrow a lot from the right shift untempering function; if we're shifting x to the
+    // left, we know that the LOWER shift_magnitude bits will be unchanged and only the UPPER bits
+    // of the mask come into play. We're still doing the one-bit-at-a-time xor thing, but now the
+    // thing we're xor-ing also has that and operation with a mask.
+    let mut x = y & !(W::MAX << shift_magnitude);
+
+    let mut bit_mask = W::ONE << shift_magnitude;
+
+    while bit_mask != W::ZERO {
+        x = x | ((y ^ ((x << shift_magnitude) & mask)) & bit_mask);
+        bit_mask = bit_mask << 1;
+    }
+
+    x
+}
+
+impl From<[u32; Mt19937::N]> for MersenneTwister {
+    fn from(state: [u32; Mt19937::N]) -> Self {
+        Self::from_state(state.to_vec())
+    }
+}
+
+impl RngCore for MersenneTwister {
+    fn next_u32(&mut self) -> u32 {
+        MersenneTwister::next_u32(self)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        // Like rand_core's `next_u64_via_u32` (and CPython's `getrandbits(64)`), the first output
+        // becomes the least-significant half
+        let low = MersenneTwister::next_u32(self) as u64;
+        let high = MersenneTwister::next_u32(self) as u64;
+
+        (high << 32) | low
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        // Each output contributes four little-endian bytes, and any leftover bytes from the last
+        // output are discarded; this matches rand_core's `fill_bytes_via_next`
+        for chunk in dest.chunks_mut(4) {
+            let bytes = MersenneTwister::next_u32(self).to_le_bytes();
+            chunk.clone_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+impl SeedableRng for MersenneTwister {
+    type Seed = [u8; 4];
+
+    fn from_seed(seed: Self::Seed) -> Self {
+        Self::new(u32::from_le_bytes(seed))
+    }
+}
+
+impl RngCore for MersenneTwister64 {
+    fn next_u32(&mut self) -> u32 {
+        // Like other 64-bit generators built on rand_core, keep the least-significant half
+        MersenneTwister64::next_u64(self) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        MersenneTwister64::next_u64(self)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(8) {
+            let bytes = MersenneTwister64::next_u64(self).to_le_bytes();
+            chunk.clone_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+impl SeedableRng for MersenneTwister64 {
+    type Seed = [u8; 8];
+
+    fn from_seed(seed: Self::Seed) -> Self {
+        Self::new(u64::from_le_bytes(seed))
+    }
+}
+
+/// The parameters of a linear congruential generator modulo 2^`modulus_bits`, `x' = a * x + c`,
+/// whose outputs are the `output_bits` bits of the state starting at bit `output_shift`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct LcgParameters {
+    pub multiplier: u64,
+    pub increment: u64,
+    pub modulus_bits: u32,
+    pub output_shift: u32,
+    pub output_bits: u32,
+}
+
+impl LcgParameters {
+    /// Microsoft's C runtime `rand()`, seeded directly by `srand()`
+    pub const MSVC_RAND: Self = Self {
+        multiplier: 214013,
+        increment: 2531011,
+        modulus_bits: 32,
+        output_shift: 16,
+        output_bits: 15,
+    };
+
+    /// glibc's `random()` with the LCG-based `TYPE_0` state (i.e. after `initstate` with an
+    /// 8-byte state buffer); the default state is a [GlibcRandom] instead
+    pub const GLIBC_TYPE_0: Self = Self {
+        multiplier: 1103515245,
+        increment: 12345,
+        modulus_bits: 31,
+        output_shift: 0,
+        output_bits: 31,
+    };
+
+    /// `java.util.Random`, whose `next(32)` returns the top 32 bits of the state; see
+    /// [JavaRandom]
+    pub const JAVA: Self = Self {
+        multiplier: 0x5deece66d,
+        increment: 0xb,
+        modulus_bits: 48,
+        output_shift: 16,
+        output_bits: 32,
+    };
+
+    fn modulus_mask(&self) -> u64 {
+        u64::MAX >> (u64::BITS - self.modulus_bits)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Lcg {
+    parameters: LcgParameters,
+    state: u64,
+}
+
+impl Lcg {
+    pub fn new(parameters: LcgParameters, state: u64) -> Self {
+        Self {
+            parameters,
+            state: state & parameters.modulus_mask(),
+        }
+    }
+
+    pub fn state(&self) -> u64 {
+        self.state
+    }
+
+    pub fn next_state(&mut self) -> u64 {
+        self.state = self.parameters.multiplier
+            .wrapping_mul(self.state)
+            .wrapping_add(self.parameters.increment)
+            & self.parameters.modulus_mask();
+
+        self.state
+    }
+
+    pub fn next_output(&mut self) -> u64 {
+        let state = self.next_state();
+        (state >> self.parameters.output_shift) & (u64::MAX >> (u64::BITS - self.parameters.output_bits))
+    }
+
+    /// Recovers a generator's state from consecutive outputs, and returns a generator whose next
+    /// output follows the last observed output.
+    ///
+    /// The first output pins down the bits of the state behind it, except for the `output_shift`
+    /// bits below the output, which are brute-forced against the remaining outputs; this is only
+    /// practical for generators that hide a few dozen bits at most (e.g. 16 for
+    /// `java.util.Random`). Any bits above the output never influence the bits below them, so
+    /// they're left as zero. Returns `None` if there are no outputs, or if no state or more than
+    /// one state matches the outputs.
+    pub fn clone_from_outputs(parameters: LcgParameters, outputs: &[u64]) -> Option<Self> {
+        let output_shift = parameters.output_shift;
+        let (&first_output, later_outputs) = outputs.split_first()?;
+
+        let mut candidates = (0..1u64 << output_shift)
+            .map(|low_bits| (first_output << output_shift) | low_bits)
+            .filter(|&state| {
+                let mut lcg = Self::new(parameters, state);
+                later_outputs.iter().all(|&output| lcg.next_output() == output)
+            });
+
+        match (candidates.next(), candidates.next()) {
+            (Some(state), None) => {
+                let mut lcg = Self::new(parameters, state);
+
+                for _ in 1..outputs.len() {
+                    lcg.next_state();
+                }
+
+                Some(lcg)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// A reimplementation of `java.util.Random`.
+#[derive(Clone, Debug)]
+pub struct JavaRandom {
+    lcg: Lcg,
+}
+
+impl JavaRandom {
+    pub fn new(seed: i64) -> Self {
+        Self {
+            lcg: Lcg::new(LcgParameters::JAVA, seed as u64 ^ LcgParameters::JAVA.multiplier),
+        }
+    }
+
+    fn next(&mut self, bits: u32) -> i32 {
+        (self.lcg.next_state() >> (LcgParameters::JAVA.modulus_bits - bits)) as i32
+    }
+
+    pub fn next_int(&mut self) -> i32 {
+        self.next(32)
+    }
+
+    /// Returns an integer in [0, bound) the same way as `nextInt(bound)`.
+    pub fn next_int_bounded(&mut self, bound: i32) -> i32 {
+        assert!(bound > 0);
+
+        let mut r = self.next(31);
+        let m = bound - 1;
+
+        if bound & m == 0 {
+            return ((bound as i64 * r as i64) >> 31) as i32;
+        }
+
+        // Reject values from the final, partial range of `bound` values (detected by overflow)
+        let mut u = r;
+
+        loop {
+            r = u % bound;
+
+            if u.wrapping_sub(r).wrapping_add(m) >= 0 {
+                return r;
+            }
+
+            u = self.next(31);
+        }
+    }
+
+    pub fn next_long(&mut self) -> i64 {
+        ((self.next(32) as i64) << 32).wrapping_add(self.next(32) as i64)
+    }
+
+    pub fn next_boolean(&mut self) -> bool {
+        self.next(1) != 0
+    }
+
+    pub fn next_double(&mut self) -> f64 {
+        (((self.next(26) as i64) << 27) + self.next(27) as i64) as f64 * (1.0 / (1u64 << 53) as f64)
+    }
+
+    /// Recovers a generator from two or more consecutive `nextInt()` outputs, and returns a
+    /// generator whose next output follows the last observed output.
+    pub fn clone_from_next_ints(outputs: &[i32]) -> Option<Self> {
+        let outputs: Vec<u64> = outputs.iter().map(|&output| output as u32 as u64).collect();
+
+        Lcg::clone_from_outputs(LcgParameters::JAVA, &outputs).map(|lcg| Self { lcg })
+    }
+
+    /// Recovers a generator from a single `nextLong()` output, which is built from two
+    /// consecutive `next(32)` outputs.
+    pub fn clone_from_next_long(output: i64) -> Option<Self> {
+        // The low half was sign-extended before being added to the high half
+        let low = output as i32;
+        let high = (output.wrapping_sub(low as i64) >> 32) as i32;
+
+        Self::clone_from_next_ints(&[high, low])
+    }
+}
+
+const GLIBC_RANDOM_DEGREE: usize = 31;
+const GLIBC_RANDOM_SEPARATION: usize = 3;
+const GLIBC_RANDOM_DISCARDED_OUTPUTS: usize = 310;
+
+/// A reimplementation of glibc's default `rand()`/`random()` (the `TYPE_3` additive feedback
+/// generator), where `r[i] = r[i - 3] + r[i - 31]` and each output is `r[i] >> 1`.
+#[derive(Clone, Debug)]
+pub struct GlibcRandom {
+    // state[index] holds r[i - 31] for the next output
+    state: [u32; GLIBC_RANDOM_DEGREE],
     index: usize,
 }
 
-impl MersenneTwister {
-    pub fn new(seed: u32) -> Self {
-        let mut state = [0; N];
-        state[0] = seed;
+impl GlibcRandom {
+    /// Seeds a generator the same way as `srand(seed)`.
+    pub fn new(seed: u32) -> Self {
+        let mut r = vec![if seed == 0 { 1 } else { seed }];
+
+        for i in 1..GLIBC_RANDOM_DEGREE {
+            // 16807 * r[i - 1] % 2147483647, computed with Schrage's method on signed words
+            let previous = r[i - 1] as i32;
+            let word = 16807 * (previous % 127773) - 2836 * (previous / 127773);
+
+            r.push(if word < 0 { word + 2147483647 } else { word } as u32);
+        }
+
+        for i in GLIBC_RANDOM_DEGREE..GLIBC_RANDOM_DEGREE + GLIBC_RANDOM_SEPARATION {
+            r.push(r[i - GLIBC_RANDOM_DEGREE]);
+        }
+
+        let mut state = [0; GLIBC_RANDOM_DEGREE];
+        state.copy_from_slice(&r[GLIBC_RANDOM_SEPARATION..]);
+
+        let mut random = Self { state, index: 0 };
+
+        for _ in 0..GLIBC_RANDOM_DISCARDED_OUTPUTS {
+            random.next_u31();
+        }
+
+        random
+    }
+
+    /// Returns the next output, which (like `rand()`) is in [0, 2^31).
+    pub fn next_u31(&mut self) -> u32 {
+        let word = self.state[self.index]
+            .wrapping_add(self.state[(self.index + GLIBC_RANDOM_DEGREE - GLIBC_RANDOM_SEPARATION) % GLIBC_RANDOM_DEGREE]);
+
+        self.state[self.index] = word;
+        self.index = (self.index + 1) % GLIBC_RANDOM_DEGREE;
+
+        word >> 1
+    }
+
+    /// Recovers a generator's state from consecutive outputs, and returns a generator whose next
+    /// output follows the last observed output.
+    ///
+    /// Each output is the sum of the outputs 3 and 31 places before it, plus a carry that's set
+    /// only when the hidden low bits of both of those words are set. Those low bits follow the
+    /// same recurrence over GF(2), so every carry that shows up gives us two linear equations in
+    /// the 31 low bits behind the first outputs. A couple hundred outputs is usually plenty;
+    /// returns `None` if the outputs don't pin down the state or aren't consistent with it.
+    pub fn clone_from_outputs(outputs: &[u32]) -> Option<Self> {
+        const DEGREE: usize = GLIBC_RANDOM_DEGREE;
+
+        if outputs.len() < DEGREE {
+            return None;
+        }
+
+        // low_bits[i] expresses the low bit of the word behind output i in terms of the first 31
+        let mut low_bits: Vec<BitVector> = (0..DEGREE).map(|i| BitVector::unit(DEGREE, i)).collect();
+        let mut system = LinearSystem::new(DEGREE);
+
+        for i in DEGREE..outputs.len() {
+            let mut low_bit = low_bits[i - GLIBC_RANDOM_SEPARATION].clone();
+            low_bit ^= &low_bits[i - DEGREE];
+            low_bits.push(low_bit);
+
+            let carry = outputs[i]
+                .wrapping_sub(outputs[i - GLIBC_RANDOM_SEPARATION])
+                .wrapping_sub(outputs[i - DEGREE])
+                & 0x7fffffff;
+
+            match carry {
+                0 => {}
+                1 => {
+                    system.add_equation(low_bits[i - GLIBC_RANDOM_SEPARATION].clone(), true).ok()?;
+                    system.add_equation(low_bits[i - DEGREE].clone(), true).ok()?;
+                }
+                _ => return None,
+            }
+        }
+
+        if system.rank() < DEGREE {
+            return None;
+        }
+
+        let solution = system.solve();
+        let mut state = [0; DEGREE];
+
+        for (i, word) in state.iter_mut().enumerate() {
+            *word = (outputs[i] << 1) | solution.get(i) as u32;
+        }
+
+        let mut random = Self { state, index: 0 };
+
+        if !outputs[DEGREE..].iter().all(|&output| random.next_u31() == output) {
+            return None;
+        }
+
+        Some(random)
+    }
+}
+
+const XORSHIFT_SHIFTS: (u32, u32, u32) = (23, 17, 26);
 
-        for i in 1..state.len() {
-            state[i] = F.wrapping_mul(state[i - 1] ^ (state[i - 1] >> (u32::BITS - 2))).wrapping_add(i as u32);
-        }
+/// xorshift128+ with the shift constants used by V8, whose `Math.random()` is built on it.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct XorShift128Plus {
+    state0: u64,
+    state1: u64,
+}
 
-        Self {
-            state,
-            index: N,
-        }
+impl XorShift128Plus {
+    pub fn new(state0: u64, state1: u64) -> Self {
+        assert!(state0 != 0 || state1 != 0);
+
+        Self { state0, state1 }
     }
 
-    pub fn next_u32(&mut self) -> u32 {
-        if self.index == N {
-            self.twist();
-        }
+    fn step(&mut self) {
+        let (a, b, c) = XORSHIFT_SHIFTS;
 
-        let mut y = self.state[self.index];
-        y ^= (y >> U) & D;
-        y ^= (y << S) & B;
-        y ^= (y << T) & C;
-        y ^= y >> L;
+        let mut s1 = self.state0;
+        let s0 = self.state1;
 
-        self.index += 1;
+        self.state0 = s0;
+        s1 ^= s1 << a;
+        s1 ^= s1 >> b;
+        s1 ^= s0;
+        s1 ^= s0 >> c;
+        self.state1 = s1;
+    }
 
-        y
+    /// Returns the generator's namesake output, the sum of its two state words.
+    pub fn next_u64(&mut self) -> u64 {
+        let sum = self.state0.wrapping_add(self.state1);
+        self.step();
+
+        sum
     }
 
-    fn twist(&mut self) {
-        for i in 0..self.state.len() {
-            let x = (self.state[i] & UPPER_MASK) | (self.state[(i + 1) % N] & LOWER_MASK);
-            let mut x_a = x >> 1;
+    /// Returns a float in [0, 1) the same way as V8's `Math.random()`, which skips the sum
+    /// entirely and uses the top 52 bits of the first state word as the mantissa of a float in
+    /// [1, 2) before subtracting 1.
+    ///
+    /// V8 generates these in batches of 64 and hands each batch out in reverse order, so
+    /// consecutive `Math.random()` values within a batch come from consecutive calls to this
+    /// method in the opposite order.
+    pub fn next_v8_double(&mut self) -> f64 {
+        self.step();
+
+        f64::from_bits((self.state0 >> 12) | 0x3ff0000000000000) - 1.0
+    }
+
+    /// Recovers a generator from the top `bits` bits of the first state word after each of a
+    /// series of consecutive steps, and returns a generator whose next output follows the last
+    /// observed output. This covers [XorShift128Plus::next_v8_double] (52 bits per output) as
+    /// well as truncated values like `Math.floor(Math.random() * 2 ** bits)`.
+    ///
+    /// The step function is linear over GF(2), so running it symbolically turns each observed
+    /// bit into a linear equation in the 128 bits of the initial state. The low bits of the state
+    /// take a few steps to reach the top bits, so this needs a few more than 128 / `bits` outputs
+    /// (e.g. four outputs from `Math.random()`). Returns `None` if the outputs don't pin down the
+    /// state.
+    pub fn clone_from_top_bits(outputs: &[u64], bits: u32) -> Option<Self> {
+        const STATE_BITS: usize = 2 * u64::BITS as usize;
+        const WORD_BITS: usize = u64::BITS as usize;
+
+        assert!((1..=u64::BITS).contains(&bits));
 
-            if x % 2 != 0 {
-                x_a ^= A;
+        let (a, b, c) = XORSHIFT_SHIFTS;
+
+        // Each symbolic word is 64 bit vectors, least-significant bit first
+        let mut state0: Vec<BitVector> = (0..WORD_BITS).map(|i| BitVector::unit(STATE_BITS, i)).collect();
+        let mut state1: Vec<BitVector> = (0..WORD_BITS).map(|i| BitVector::unit(STATE_BITS, WORD_BITS + i)).collect();
+
+        let mut system = LinearSystem::new(STATE_BITS);
+
+        for &output in outputs {
+            let mut s1 = state0;
+            let s0 = state1.clone();
+
+            state0 = s0.clone();
+            s1 = symbolic_xor_shift(&s1, &s1, a as isize);
+            s1 = symbolic_xor_shift(&s1, &s1, -(b as isize));
+            s1 = symbolic_xor_shift(&s1, &s0, 0);
+            state1 = symbolic_xor_shift(&s1, &s0, -(c as isize));
+
+            for bit in 0..bits as usize {
+                let state_bit = WORD_BITS - bits as usize + bit;
+                system.add_equation(state0[state_bit].clone(), output & (1 << bit) != 0).ok()?;
             }
+        }
 
-            self.state[i] = self.state[(i + M) % N] ^ x_a;
+        if system.rank() < STATE_BITS {
+            return None;
         }
 
-        self.index = 0;
+        let solution = system.solve();
+        let word = |offset: usize| (0..WORD_BITS).fold(0, |word, i| word | (solution.get(offset + i) as u64) << i);
+
+        let mut generator = Self::new(word(0), word(WORD_BITS));
+
+        for _ in outputs {
+            generator.step();
+        }
+
+        Some(generator)
+    }
+
+    /// Recovers a generator from consecutive outputs of [XorShift128Plus::next_v8_double]; see
+    /// [XorShift128Plus::clone_from_top_bits].
+    pub fn clone_from_v8_doubles(outputs: &[f64]) -> Option<Self> {
+        let outputs: Vec<u64> = outputs.iter()
+            .map(|&output| (output * (1u64 << 52) as f64) as u64)
+            .collect();
+
+        Self::clone_from_top_bits(&outputs, 52)
     }
 }
 
-impl From<[u32; N]> for MersenneTwister {
-    fn from(state: [u32; N]) -> Self {
-        Self {
-            state,
-            index: N,
+/// Returns `x ^ (y << shift)` for symbolic words, where negative shifts go to the right.
+fn symbolic_xor_shift(x: &[BitVector], y: &[BitVector], shift: isize) -> Vec<BitVector> {
+    let mut result = x.to_vec();
+
+    for (i, bit) in result.iter_mut().enumerate() {
+        let source = i as isize - shift;
+
+        if (0..y.len() as isize).contains(&source) {
+            *bit ^= &y[source as usize];
         }
     }
+
+    result
 }
 
 #[cfg(test)]
 mod test {
-    use crate::random::MersenneTwister;
+    use rand::{Rng, RngCore, SeedableRng};
+    use rand::seq::SliceRandom;
+    use crate::random::*;
+
+    const N: usize = Mt19937::N;
 
     // Test vectors via https://gist.github.com/mimoo/8e5d80a2e236b8b6f5ed
     const EXPECTED_VALUES: [u32; 10] = [
@@ -101,4 +1231,488 @@ mod test {
             assert_eq!(EXPECTED_VALUES[i], mt.next_u32());
         }
     }
+
+    #[test]
+    fn test_default_seed() {
+        // C++ requires the 10,000th output of a default-constructed `std::mt19937` to be
+        // 4123659995 (and 9981545732273789042 for `std::mt19937_64`)
+        let mut mt = MersenneTwister::new(5489);
+
+        for _ in 0..9999 {
+            mt.next_u32();
+        }
+
+        assert_eq!(4123659995, mt.next_u32());
+    }
+
+    #[test]
+    fn test_mersenne_twister_64() {
+        // Expected values via `std::mt19937_64`
+        let mut mt = MersenneTwister64::new(5489);
+
+        for expected in [14514284786278117030, 4620546740167642908, 13109570281517897720, 17462938647148434322] {
+            assert_eq!(expected, mt.next_u64());
+        }
+
+        for _ in 4..9999 {
+            mt.next_u64();
+        }
+
+        assert_eq!(9981545732273789042, mt.next_u64());
+
+        let mut mt = MersenneTwister64::new(0x0123456789abcdef);
+
+        for expected in [13169394222641354532, 341231063478520994, 9087906688904613890] {
+            assert_eq!(expected, mt.next_u64());
+        }
+    }
+
+    #[test]
+    fn test_custom_parameters() {
+        // MT11213B from Boost, which C++ can produce as
+        // `std::mersenne_twister_engine<uint32_t, 32, 351, 175, 19, 0xccab8ee7, 11, 0xffffffff, 7,
+        // 0x31b6ab00, 15, 0xffe50000, 17, 1812433253>`
+        struct Mt11213B;
+
+        impl TwisterParameters for Mt11213B {
+            type Word = u32;
+
+            const N: usize = 351;
+            const M: usize = 175;
+            const R: u32 = 19;
+            const A: u32 = 0xccab8ee7;
+            const U: u32 = 11;
+            const D: u32 = 0xffffffff;
+            const S: u32 = 7;
+            const B: u32 = 0x31b6ab00;
+            const T: u32 = 15;
+            const C: u32 = 0xffe50000;
+            const L: u32 = 17;
+            const F: u32 = 1812433253;
+        }
+
+        let mut mt = Twister::<Mt11213B>::new(5489);
+
+        for expected in [4013899583, 1879581045, 3673615093, 706127422, 2743081796] {
+            assert_eq!(expected, mt.next_word());
+        }
+
+        for _ in 5..9999 {
+            mt.next_word();
+        }
+
+        assert_eq!(3809585648, mt.next_word());
+
+        let outputs: Vec<u32> = (0..Mt11213B::N).map(|_| mt.next_word()).collect();
+        let mut cloned = Twister::<Mt11213B>::clone_from_outputs(&outputs);
+
+        assert_eq!(mt.next_word(), cloned.next_word());
+        assert_eq!(Some(5489), cloned.recover_seed(10000 + Mt11213B::N * 2));
+    }
+
+    #[test]
+    fn test_first_output() {
+        for _ in 0..16 {
+            let seed = rand::thread_rng().next_u32();
+            assert_eq!(MersenneTwister::new(seed).next_u32(), MersenneTwister::first_output(seed));
+
+            let seed = rand::thread_rng().next_u64();
+            assert_eq!(MersenneTwister64::new(seed).next_u64(), MersenneTwister64::first_output(seed));
+        }
+    }
+
+    #[test]
+    fn test_nth_output() {
+        let seed = rand::thread_rng().next_u32();
+        let mut mt = MersenneTwister::new(seed);
+
+        for n in 0..Mt19937::N - Mt19937::M {
+            assert_eq!(mt.next_u32(), MersenneTwister::nth_output(seed, n));
+        }
+    }
+
+    #[test]
+    fn test_from_key() {
+        // Expected values from http://www.math.sci.hiroshima-u.ac.jp/m-mat/MT/MT2002/CODES/mt19937ar.out
+        let mut mt = MersenneTwister::from_key(&[0x123, 0x234, 0x345, 0x456]);
+
+        for expected in [1067595299, 955945823, 477289528, 4107218783, 4228976476] {
+            assert_eq!(expected, mt.next_u32());
+        }
+    }
+
+    // Expected values in the following tests were captured from CPython 3.11 (e.g.
+    // `random.Random(42).getrandbits(32)`)
+    #[test]
+    fn test_python_seeded() {
+        let mut mt = MersenneTwister::python_seeded(42);
+
+        for expected in [2746317213, 478163327, 107420369, 3184935163, 1181241943] {
+            assert_eq!(expected, mt.next_u32());
+        }
+
+        assert_eq!(3626764237, MersenneTwister::python_seeded(0).next_u32());
+        assert_eq!(485306839, MersenneTwister::python_seeded(1 << 32).next_u32());
+        assert_eq!(2635837658, MersenneTwister::python_seeded((1 << 40) + 7).next_u32());
+    }
+
+    #[test]
+    fn test_python_random() {
+        let mut mt = MersenneTwister::python_seeded(42);
+
+        assert_eq!(0.6394267984578837, mt.python_random());
+        assert_eq!(0.025010755222666936, mt.python_random());
+    }
+
+    #[test]
+    fn test_python_getrandbits() {
+        let mut mt = MersenneTwister::python_seeded(42);
+
+        assert_eq!(vec![163], mt.python_getrandbits(8));
+
+        assert_eq!(
+            461366972257415551,
+            mt.python_getrandbits(64)
+                .iter()
+                .rev()
+                .fold(0u64, |r, &word| r << 32 | word as u64)
+        );
+
+        assert_eq!(vec![0xbdd640fb, 0x46685257, 0x3eb13b90, 0x03], mt.python_getrandbits(100));
+        assert_eq!(vec![0x23b8c1e9, 0x1], mt.python_getrandbits(33));
+        assert!(mt.python_getrandbits(0).is_empty());
+    }
+
+    #[test]
+    fn test_python_randbelow() {
+        let mut mt = MersenneTwister::python_seeded(42);
+
+        for expected in [654, 114, 25, 759, 281] {
+            assert_eq!(expected, mt.python_randbelow(1000));
+        }
+
+        // random.randint(1, 6)
+        assert_eq!(2, 1 + mt.python_randbelow(6));
+    }
+
+    #[test]
+    fn test_untemper_right_shift() {
+        for _ in 0..1024 {
+            let x = rand::thread_rng().next_u32();
+
+            for magnitude in 1..32 {
+                let y = x ^ (x >> magnitude);
+                assert_eq!(x, untemper_right_shift(y, magnitude, u32::MAX));
+            }
+        }
+    }
+
+    #[test]
+    fn test_untemper_left_shift() {
+        for _ in 0..1024 {
+            let x = rand::thread_rng().next_u32();
+            let mask = rand::thread_rng().next_u32();
+
+            for magnitude in 1..32 {
+                let y = x ^ ((x << magnitude) & mask);
+                assert_eq!(x, untemper_left_shift(y, magnitude, mask));
+            }
+        }
+    }
+
+    #[test]
+    fn test_untemper() {
+        for _ in 0..1024 {
+            let x = rand::thread_rng().next_u32();
+
+            assert_eq!(x, MersenneTwister::untemper(MersenneTwister::temper(x)));
+        }
+    }
+
+    #[test]
+    fn test_clone_from_outputs() {
+        let mut original = MersenneTwister::new(rand::thread_rng().next_u32());
+
+        // Deliberately start observing somewhere other than a twist boundary
+        for _ in 0..rand::thread_rng().gen_range(1..N * 3) {
+            original.next_u32();
+        }
+
+        let mut outputs = [0; N];
+
+        for output in outputs.iter_mut() {
+            *output = original.next_u32();
+        }
+
+        let mut cloned = MersenneTwister::clone_from_outputs(&outputs);
+
+        for _ in 0..N * 2 {
+            assert_eq!(original.next_u32(), cloned.next_u32());
+        }
+    }
+
+    #[test]
+    fn test_clone_from_observed_bits() {
+        let mut original = MersenneTwister::new(rand::thread_rng().next_u32());
+
+        for _ in 0..rand::thread_rng().gen_range(0..N) {
+            original.next_u32();
+        }
+
+        // Only the top byte of each output is visible
+        let observations: Vec<ObservedBit> = (0..2600)
+            .flat_map(|i| ObservedBit::from_masked_output(i, original.next_u32(), 0xff000000))
+            .collect();
+
+        let mut cloned = MersenneTwister::clone_from_observed_bits(&observations).unwrap();
+
+        for _ in 0..N * 2 {
+            assert_eq!(original.next_u32(), cloned.next_u32());
+        }
+    }
+
+    #[test]
+    fn test_clone_from_observed_bits_underdetermined() {
+        let mut original = MersenneTwister::new(rand::thread_rng().next_u32());
+
+        let observations: Vec<ObservedBit> = (0..100)
+            .flat_map(|i| ObservedBit::from_masked_output(i, original.next_u32(), 0xff000000))
+            .collect();
+
+        assert!(MersenneTwister::clone_from_observed_bits(&observations).is_none());
+    }
+
+    #[test]
+    fn test_previous_u32() {
+        let mut mt = MersenneTwister::new(rand::thread_rng().next_u32());
+        let outputs: Vec<u32> = (0..N * 3 + 17).map(|_| mt.next_u32()).collect();
+
+        for &output in outputs.iter().rev() {
+            assert_eq!(output, mt.previous_u32());
+        }
+
+        for &output in &outputs {
+            assert_eq!(output, mt.next_u32());
+        }
+    }
+
+    #[test]
+    fn test_invert_init_genrand_step() {
+        for _ in 0..1024 {
+            let word = rand::thread_rng().next_u32();
+            let i = rand::thread_rng().gen_range(1..N);
+
+            assert_eq!(
+                word,
+                MersenneTwister::invert_init_genrand_step(MersenneTwister::init_genrand_step(word, i), i)
+            );
+        }
+    }
+
+    #[test]
+    fn test_recover_seed() {
+        let seed = rand::thread_rng().next_u32();
+        let mut original = MersenneTwister::new(seed);
+
+        let skipped_outputs = rand::thread_rng().gen_range(0..N * 4);
+
+        for _ in 0..skipped_outputs {
+            original.next_u32();
+        }
+
+        let mut outputs = [0; N];
+
+        for output in outputs.iter_mut() {
+            *output = original.next_u32();
+        }
+
+        let cloned = MersenneTwister::clone_from_outputs(&outputs);
+
+        assert_eq!(Some(seed), cloned.recover_seed(skipped_outputs + N * 2));
+        assert_eq!(None, cloned.recover_seed(skipped_outputs));
+    }
+
+    #[test]
+    fn test_recover_seed_64() {
+        let seed = rand::thread_rng().next_u64();
+        let mut original = MersenneTwister64::new(seed);
+
+        for _ in 0..rand::thread_rng().gen_range(0..Mt19937_64::N * 4) {
+            original.next_u64();
+        }
+
+        let outputs: Vec<u64> = (0..Mt19937_64::N).map(|_| original.next_u64()).collect();
+        let mut cloned = MersenneTwister64::clone_from_outputs(&outputs);
+
+        assert_eq!(Some(seed), cloned.recover_seed(Mt19937_64::N * 7));
+
+        for _ in 0..Mt19937_64::N * 2 {
+            assert_eq!(original.next_u64(), cloned.next_u64());
+        }
+    }
+
+    #[test]
+    fn test_rng_core() {
+        let mut mt = MersenneTwister::from_seed(1131464071u32.to_le_bytes());
+
+        assert_eq!(
+            (EXPECTED_VALUES[1] as u64) << 32 | EXPECTED_VALUES[0] as u64,
+            RngCore::next_u64(&mut mt)
+        );
+
+        let mut bytes = [0; 7];
+        mt.fill_bytes(&mut bytes);
+
+        assert_eq!(EXPECTED_VALUES[2].to_le_bytes(), bytes[..4]);
+        assert_eq!(EXPECTED_VALUES[3].to_le_bytes()[..3], bytes[4..]);
+        assert_eq!(EXPECTED_VALUES[4], RngCore::next_u32(&mut mt));
+    }
+
+    #[test]
+    fn test_clone() {
+        let mut original = MersenneTwister::new(rand::thread_rng().next_u32());
+        let mut cloned = original.clone();
+
+        let mut items: Vec<u32> = (0..100).collect();
+        let mut cloned_items = items.clone();
+
+        items.shuffle(&mut original);
+        cloned_items.shuffle(&mut cloned);
+
+        assert_eq!(items, cloned_items);
+        assert_eq!(original.gen_range(0..1000), cloned.gen_range(0..1000));
+    }
+
+    #[test]
+    fn test_lcg() {
+        // srand(1) with Microsoft's C runtime
+        let mut lcg = Lcg::new(LcgParameters::MSVC_RAND, 1);
+
+        for expected in [41, 18467, 6334, 26500, 19169] {
+            assert_eq!(expected, lcg.next_output());
+        }
+
+        let outputs: Vec<u64> = (0..4).map(|_| lcg.next_output()).collect();
+        let mut cloned = Lcg::clone_from_outputs(LcgParameters::MSVC_RAND, &outputs).unwrap();
+
+        for _ in 0..100 {
+            assert_eq!(lcg.next_output(), cloned.next_output());
+        }
+
+        let mut lcg = Lcg::new(LcgParameters::GLIBC_TYPE_0, rand::thread_rng().next_u64());
+        let mut cloned = Lcg::clone_from_outputs(LcgParameters::GLIBC_TYPE_0, &[lcg.next_output()]).unwrap();
+
+        assert_eq!(lcg.next_output(), cloned.next_output());
+
+        assert!(Lcg::clone_from_outputs(LcgParameters::GLIBC_TYPE_0, &[]).is_none());
+        assert!(JavaRandom::clone_from_next_ints(&[]).is_none());
+    }
+
+    // Expected values in the following tests were captured from OpenJDK 17
+    #[test]
+    fn test_java_random() {
+        let mut random = JavaRandom::new(0);
+
+        assert_eq!(-1155484576, random.next_int());
+        assert_eq!(-3109364765729502342, random.next_long());
+        assert_eq!(0.6063452159973596, random.next_double());
+        assert!(!random.next_boolean());
+        assert_eq!(1, random.next_int_bounded(10));
+        assert_eq!(1, random.next_int_bounded(16));
+        assert_eq!(283218712, random.next_int_bounded(1000000007));
+
+        let mut random = JavaRandom::new(42);
+
+        for expected in [-1170105035, 234785527, -1360544799] {
+            assert_eq!(expected, random.next_int());
+        }
+
+        let mut random = JavaRandom::new(-123456789012345);
+
+        assert_eq!(6099873391810278226, random.next_long());
+        assert_eq!(-8148537296809300264, random.next_long());
+    }
+
+    #[test]
+    fn test_java_random_clone() {
+        let mut random = JavaRandom::new(rand::thread_rng().gen());
+
+        let mut cloned = JavaRandom::clone_from_next_ints(&[random.next_int(), random.next_int()]).unwrap();
+        assert_eq!(random.next_long(), cloned.next_long());
+
+        let mut cloned = JavaRandom::clone_from_next_long(random.next_long()).unwrap();
+        assert_eq!(random.next_int_bounded(1000), cloned.next_int_bounded(1000));
+    }
+
+    #[test]
+    fn test_glibc_random() {
+        // Expected values from glibc 2.36 (e.g. `srand(1); rand();`)
+        let mut random = GlibcRandom::new(1);
+
+        for expected in [1804289383, 846930886, 1681692777, 1714636915, 1957747793] {
+            assert_eq!(expected, random.next_u31());
+        }
+
+        assert_eq!(1804289383, GlibcRandom::new(0).next_u31());
+
+        let mut random = GlibcRandom::new(123456789);
+
+        for expected in [1965102536, 1639725855, 706684578] {
+            assert_eq!(expected, random.next_u31());
+        }
+
+        let mut random = GlibcRandom::new(5);
+
+        for _ in 0..1000 {
+            random.next_u31();
+        }
+
+        assert_eq!(321822539, random.next_u31());
+    }
+
+    #[test]
+    fn test_glibc_random_clone() {
+        let mut random = GlibcRandom::new(rand::thread_rng().next_u32());
+        let outputs: Vec<u32> = (0..400).map(|_| random.next_u31()).collect();
+
+        let mut cloned = GlibcRandom::clone_from_outputs(&outputs).unwrap();
+
+        for _ in 0..1000 {
+            assert_eq!(random.next_u31(), cloned.next_u31());
+        }
+
+        assert!(GlibcRandom::clone_from_outputs(&outputs[..40]).is_none());
+    }
+
+    #[test]
+    fn test_xorshift128_plus_clone() {
+        let mut generator = XorShift128Plus::new(rand::thread_rng().next_u64(), rand::thread_rng().next_u64());
+
+        let outputs: Vec<f64> = (0..4).map(|_| generator.next_v8_double()).collect();
+        let mut cloned = XorShift128Plus::clone_from_v8_doubles(&outputs).unwrap();
+
+        assert_eq!(generator, cloned);
+        assert_eq!(generator.next_u64(), cloned.next_u64());
+
+        // Six bits per output, as from `Math.floor(Math.random() * 64)`
+        let outputs: Vec<u64> = (0..30).map(|_| (generator.next_v8_double() * 64.0) as u64).collect();
+        let mut cloned = XorShift128Plus::clone_from_top_bits(&outputs, 6).unwrap();
+
+        assert_eq!(generator.next_v8_double(), cloned.next_v8_double());
+        assert!(XorShift128Plus::clone_from_top_bits(&outputs[..10], 6).is_none());
+    }
+
+    #[test]
+    fn test_xorshift128_plus_v8() {
+        // Consecutive `Math.random()` values from a fresh Node.js 20 process; V8 hands these out
+        // in reverse order of generation
+        let mut observed = [0.6958570192156566, 0.6499337126378661, 0.5580590491251693, 0.8173726766867371, 0.9624481990477869, 0.5531978210239599];
+        observed.reverse();
+
+        let mut cloned = XorShift128Plus::clone_from_v8_doubles(&observed[..4]).unwrap();
+
+        for &expected in &observed[4..] {
+            assert_eq!(expected, cloned.next_v8_double());
+        }
+    }
 }
@@ -0,0 +1,211 @@
+use std::thread;
+use rand::RngCore;
+
+pub struct Rc4 {
+    state: [u8; 256],
+    i: u8,
+    j: u8,
+}
+
+impl Rc4 {
+    pub fn new(key: &[u8]) -> Self {
+        assert!(!key.is_empty() && key.len() <= 256);
+
+        let mut state = [0; 256];
+
+        for (i, s) in state.iter_mut().enumerate() {
+            *s = i as u8;
+        }
+
+        let mut j: u8 = 0;
+
+        for i in 0..state.len() {
+            j = j.wrapping_add(state[i]).wrapping_add(key[i % key.len()]);
+            state.swap(i, j as usize);
+        }
+
+        Self { state, i: 0, j: 0 }
+    }
+
+    pub fn next_u8(&mut self) -> u8 {
+        self.i = self.i.wrapping_add(1);
+        self.j = self.j.wrapping_add(self.state[self.i as usize]);
+        self.state.swap(self.i as usize, self.j as usize);
+
+        self.state[self.state[self.i as usize].wrapping_add(self.state[self.j as usize]) as usize]
+    }
+}
+
+pub fn rc4_transform(text: &[u8], key: &[u8]) -> Vec<u8> {
+    let mut rc4 = Rc4::new(key);
+
+    text.iter()
+        .map(|b| b ^ rc4.next_u8())
+        .collect()
+}
+
+/// Counts how often each byte value appears at each of the given (zero-indexed) keystream
+/// positions across `samples` fresh, random 128-bit keys.
+pub fn keystream_byte_frequencies(positions: &[usize], samples: usize) -> Vec<[u64; 256]> {
+    let keystream_length = positions.iter().max().map_or(0, |p| p + 1);
+
+    parallel_byte_frequencies(positions, samples, || {
+        let mut key = [0; 16];
+        rand::thread_rng().fill_bytes(&mut key);
+
+        rc4_transform(&vec![0; keystream_length], &key)
+    })
+}
+
+/// Recovers a secret that an oracle appends to an attacker-controlled prefix before encrypting
+/// the whole thing with RC4 under a fresh key, exploiting biases in the RC4 keystream.
+///
+/// Each secret byte is pushed into the nearest of the given biased keystream positions at or
+/// after its own offset (e.g. 15 and 31, which favor 0xf0 and 0xe0, respectively), and then
+/// `samples` ciphertexts are collected for each prefix length. Bias tables for those positions
+/// are computed locally from the same number of random keys.
+pub fn recover_secret_suffix(
+    oracle: impl Fn(&[u8]) -> Vec<u8> + Sync,
+    secret_length: usize,
+    biased_positions: &[usize],
+    samples: usize,
+) -> Vec<u8> {
+    let keystream_frequencies = keystream_byte_frequencies(biased_positions, samples);
+
+    let mut positions_by_prefix_length = vec![vec![]; biased_positions.iter().max().map_or(0, |p| p + 1)];
+
+    for i in 0..secret_length {
+        let position = biased_positions.iter()
+            .copied()
+            .filter(|&p| p >= i)
+            .min()
+            .expect("Secret extends past the last biased position");
+
+        positions_by_prefix_length[position - i].push(position);
+    }
+
+    let mut secret = vec![0; secret_length];
+
+    for (prefix_length, positions) in positions_by_prefix_length.iter().enumerate() {
+        if positions.is_empty() {
+            continue;
+        }
+
+        let prefix = vec![b'A'; prefix_length];
+        let ciphertext_frequencies = parallel_byte_frequencies(positions, samples, || oracle(&prefix));
+
+        for (position, frequencies) in positions.iter().zip(ciphertext_frequencies.iter()) {
+            let bias_index = biased_positions.iter().position(|p| p == position).unwrap();
+
+            secret[position - prefix_length] =
+                most_likely_plaintext_byte(frequencies, &keystream_frequencies[bias_index]);
+        }
+    }
+
+    secret
+}
+
+/// Picks the plaintext byte that best explains the observed ciphertext byte frequencies, given
+/// the (estimated) distribution of keystream bytes at the same position.
+pub fn most_likely_plaintext_byte(ciphertext_frequencies: &[u64; 256], keystream_frequencies: &[u64; 256]) -> u8 {
+    // A full maximum-likelihood comparison against every entry in the keystream table sounds
+    // nice, but the table would need orders of magnitude more samples than the attack itself
+    // before its noise stopped drowning out the bias. Sticking to the single most favored
+    // keystream byte is far more robust.
+    most_frequent_byte(ciphertext_frequencies) ^ most_frequent_byte(keystream_frequencies)
+}
+
+fn most_frequent_byte(frequencies: &[u64; 256]) -> u8 {
+    frequencies.iter()
+        .enumerate()
+        .max_by_key(|(_, &count)| count)
+        .map(|(b, _)| b as u8)
+        .unwrap()
+}
+
+fn parallel_byte_frequencies(
+    positions: &[usize],
+    samples: usize,
+    sample: impl Fn() -> Vec<u8> + Sync,
+) -> Vec<[u64; 256]> {
+    let thread_count = thread::available_parallelism().map_or(1, |n| n.get());
+
+    thread::scope(|scope| {
+        let handles: Vec<_> = (0..thread_count)
+            .map(|t| {
+                let sample = &sample;
+                let thread_samples = samples / thread_count + usize::from(t < samples % thread_count);
+
+                scope.spawn(move || {
+                    let mut frequencies = vec![[0u64; 256]; positions.len()];
+
+                    for _ in 0..thread_samples {
+                        let bytes = sample();
+
+                        for (frequencies, &position) in frequencies.iter_mut().zip(positions.iter()) {
+                            frequencies[bytes[position] as usize] += 1;
+                        }
+                    }
+
+                    frequencies
+                })
+            })
+            .collect();
+
+        let mut frequencies = vec![[0u64; 256]; positions.len()];
+
+        for handle in handles {
+            for (total, partial) in frequencies.iter_mut().zip(handle.join().unwrap().iter()) {
+                total.iter_mut()
+                    .zip(partial.iter())
+                    .for_each(|(a, b)| *a += b);
+            }
+        }
+
+        frequencies
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use rand::RngCore;
+    use crate::rc4::{rc4_transform, recover_secret_suffix};
+
+    #[test]
+    fn test_rc4_transform() {
+        // Test vectors from https://en.wikipedia.org/wiki/RC4#Test_vectors
+        assert_eq!(
+            hex::decode("bbf316e8d940af0ad3").unwrap(),
+            rc4_transform(b"Plaintext", b"Key")
+        );
+
+        assert_eq!(
+            hex::decode("1021bf0420").unwrap(),
+            rc4_transform(b"pedia", b"Wiki")
+        );
+
+        assert_eq!(
+            hex::decode("45a01f645fc35b383552544b9bf5").unwrap(),
+            rc4_transform(b"Attack at dawn", b"Secret")
+        );
+    }
+
+    #[test]
+    fn test_recover_secret_suffix() {
+        const SECRET: &[u8] = b"OK";
+
+        let oracle = |prefix: &[u8]| {
+            let mut key = [0; 16];
+            rand::thread_rng().fill_bytes(&mut key);
+
+            let mut plaintext = Vec::from(prefix);
+            plaintext.extend_from_slice(SECRET);
+
+            rc4_transform(&plaintext, &key)
+        };
+
+        // The second keystream byte is heavily biased toward zero (Mantin and Shamir), so a
+        // relatively small number of samples is enough here
+        assert_eq!(SECRET, recover_secret_suffix(oracle, SECRET.len(), &[1], 1 << 16));
+    }
+}
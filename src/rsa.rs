@@ -0,0 +1,494 @@
+use std::collections::HashSet;
+use std::io::{self, ErrorKind, Read, Write};
+use rand::Rng;
+use crate::bigint::{chinese_remainder, BigUint};
+use crate::primes::random_prime;
+use crate::sha1::Sha1;
+
+// Serialized numbers larger than this are rejected rather than allocated
+const MAX_SERIALIZED_BYTES: usize = 1 << 16;
+
+/// An RSA public key (n, e).
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct RsaPublicKey {
+    n: BigUint,
+    e: BigUint,
+}
+
+/// An RSA private key. Besides d, it keeps the primes and the values that private operations use
+/// to work mod p and mod q separately and recombine with the Chinese Remainder Theorem, which is
+/// about four times faster than working mod n.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RsaPrivateKey {
+    public_key: RsaPublicKey,
+    d: BigUint,
+    p: BigUint,
+    q: BigUint,
+
+    // d mod (p - 1), d mod (q - 1) and q^-1 mod p
+    d_p: BigUint,
+    d_q: BigUint,
+    q_inverse: BigUint,
+}
+
+impl RsaPublicKey {
+    pub fn new(n: BigUint, e: BigUint) -> Self {
+        Self { n, e }
+    }
+
+    pub fn n(&self) -> &BigUint {
+        &self.n
+    }
+
+    pub fn e(&self) -> &BigUint {
+        &self.e
+    }
+
+    /// Returns the size of the modulus in bits.
+    pub fn bits(&self) -> u64 {
+        self.n.bits()
+    }
+
+    /// Textbook RSA encryption: m^e mod n.
+    pub fn encrypt(&self, message: &BigUint) -> BigUint {
+        assert!(message < &self.n, "Message must be smaller than the modulus");
+
+        message.modpow(&self.e, &self.n)
+    }
+
+    /// Checks a textbook RSA signature: s^e mod n == m.
+    pub fn verify(&self, message: &BigUint, signature: &BigUint) -> bool {
+        signature < &self.n && &signature.modpow(&self.e, &self.n) == message
+    }
+
+    pub fn write_to(&self, writer: &mut impl Write) -> io::Result<()> {
+        write_biguint(writer, &self.n)?;
+        write_biguint(writer, &self.e)
+    }
+
+    pub fn read_from(reader: &mut impl Read) -> io::Result<Self> {
+        let n = read_biguint(reader)?;
+        let e = read_biguint(reader)?;
+
+        if n.is_even() || e.is_zero() || e >= n {
+            return Err(io::Error::new(ErrorKind::InvalidData, "Invalid public key"));
+        }
+
+        Ok(Self { n, e })
+    }
+}
+
+impl RsaPrivateKey {
+    /// Generates a key with a modulus of exactly `bits` bits and the given public exponent (65537
+    /// is the usual choice; 3 makes for some instructive attacks).
+    pub fn generate(rng: &mut impl Rng, bits: u64, e: &BigUint) -> Self {
+        assert!(bits >= 16, "Modulus is too small");
+        assert!(e.is_odd() && e > &BigUint::one(), "e must be odd and greater than 1");
+
+        // Both primes have their top two bits set, so their product has exactly `bits` bits
+        loop {
+            let p = random_prime(rng, bits - bits / 2);
+            let q = random_prime(rng, bits / 2);
+
+            if p == q {
+                continue;
+            }
+
+            if let Some(key) = Self::from_primes(p, q, e.clone()) {
+                return key;
+            }
+        }
+    }
+
+    /// Builds a key from its primes, or returns `None` if either "prime" is 0 or 1 or e isn't
+    /// invertible mod (p - 1)(q - 1).
+    pub fn from_primes(p: BigUint, q: BigUint, e: BigUint) -> Option<Self> {
+        if p <= BigUint::one() || q <= BigUint::one() {
+            return None;
+        }
+
+        let p_minus_one = &p - BigUint::one();
+        let q_minus_one = &q - BigUint::one();
+        let d = e.modinv(&(&p_minus_one * &q_minus_one))?;
+
+        Some(Self {
+            d_p: &d % &p_minus_one,
+            d_q: &d % &q_minus_one,
+            q_inverse: q.modinv(&p)?,
+            public_key: RsaPublicKey::new(&p * &q, e),
+            d,
+            p,
+            q,
+        })
+    }
+
+    pub fn public_key(&self) -> &RsaPublicKey {
+        &self.public_key
+    }
+
+    pub fn d(&self) -> &BigUint {
+        &self.d
+    }
+
+    pub fn p(&self) -> &BigUint {
+        &self.p
+    }
+
+    pub fn q(&self) -> &BigUint {
+        &self.q
+    }
+
+    /// Textbook RSA decryption (c^d mod n), computed with the CRT.
+    pub fn decrypt(&self, ciphertext: &BigUint) -> BigUint {
+        assert!(ciphertext < &self.public_key.n, "Ciphertext must be smaller than the modulus");
+
+        // Garner's formula: m = m_q + q * ((m_p - m_q) * q^-1 mod p)
+        let m_p = (ciphertext % &self.p).modpow(&self.d_p, &self.p);
+        let m_q = (ciphertext % &self.q).modpow(&self.d_q, &self.q);
+        let h = (m_p + &self.p - &m_q % &self.p) * &self.q_inverse % &self.p;
+
+        m_q + h * &self.q
+    }
+
+    /// Decrypts directly as c^d mod n. This is slower than [RsaPrivateKey::decrypt], but gives the
+    /// same results.
+    pub fn decrypt_without_crt(&self, ciphertext: &BigUint) -> BigUint {
+        assert!(ciphertext < &self.public_key.n, "Ciphertext must be smaller than the modulus");
+
+        ciphertext.modpow(&self.d, &self.public_key.n)
+    }
+
+    /// Textbook RSA signing: m^d mod n, computed with the CRT.
+    pub fn sign(&self, message: &BigUint) -> BigUint {
+        self.decrypt(message)
+    }
+
+    /// Writes n, e, d, p and q. The CRT values are recomputed when the key is read back.
+    pub fn write_to(&self, writer: &mut impl Write) -> io::Result<()> {
+        self.public_key.write_to(writer)?;
+
+        for value in [&self.d, &self.p, &self.q] {
+            write_biguint(writer, value)?;
+        }
+
+        Ok(())
+    }
+
+    /// Reads a key written by [RsaPrivateKey::write_to], checking that its values are consistent.
+    pub fn read_from(reader: &mut impl Read) -> io::Result<Self> {
+        let public_key = RsaPublicKey::read_from(reader)?;
+        let d = read_biguint(reader)?;
+        let p = read_biguint(reader)?;
+        let q = read_biguint(reader)?;
+
+        // n is odd, so this also rules out p = 1, q = n (which would make (p - 1)(q - 1) zero)
+        if [&p, &q].iter().any(|&prime| prime <= &BigUint::one() || prime.is_even()) {
+            return Err(io::Error::new(ErrorKind::InvalidData, "Invalid prime"));
+        }
+
+        if &p * &q != public_key.n {
+            return Err(io::Error::new(ErrorKind::InvalidData, "Primes don't match the modulus"));
+        }
+
+        Self::from_primes(p, q, public_key.e)
+            .filter(|key| key.d == d)
+            .ok_or_else(|| io::Error::new(ErrorKind::InvalidData, "Inconsistent private exponent"))
+    }
+}
+
+/// A decryption service that will decrypt any ciphertext, but only once. It remembers the SHA-1
+/// hashes of the ciphertexts it has decrypted and refuses to decrypt them again, so captured
+/// ciphertexts can't simply be replayed to it.
+pub struct DecryptionOracle {
+    private_key: RsaPrivateKey,
+    seen: HashSet<Vec<u8>>,
+}
+
+impl DecryptionOracle {
+    pub fn new(private_key: RsaPrivateKey) -> Self {
+        Self { private_key, seen: HashSet::new() }
+    }
+
+    pub fn public_key(&self) -> &RsaPublicKey {
+        self.private_key.public_key()
+    }
+
+    /// Decrypts the ciphertext, or returns `None` if it's been decrypted before (or isn't smaller
+    /// than the modulus).
+    pub fn decrypt(&mut self, ciphertext: &BigUint) -> Option<BigUint> {
+        if ciphertext >= self.public_key().n() {
+            return None;
+        }
+
+        let mut sha1 = Sha1::default();
+        sha1.update(&ciphertext.to_bytes_be());
+
+        self.seen.insert(sha1.finish())
+            .then(|| self.private_key.decrypt(ciphertext))
+    }
+}
+
+/// Recovers the plaintext of a ciphertext the oracle has already decrypted, by having it decrypt
+/// C' = s^e * C mod n instead. Textbook RSA is multiplicative, so that decrypts to s * m mod n,
+/// and dividing out the random s leaves m.
+pub fn unpadded_message_recovery(oracle: &mut DecryptionOracle, ciphertext: &BigUint, rng: &mut impl Rng) -> Option<BigUint> {
+    let public_key = oracle.public_key().clone();
+    let n = public_key.n();
+
+    // s has to be invertible mod n, which a random s almost certainly is
+    let (s, s_inverse) = loop {
+        let s = BigUint::random_below(rng, &(n - BigUint::from(2u8))) + BigUint::from(2u8);
+
+        if let Some(s_inverse) = s.modinv(n) {
+            break (s, s_inverse);
+        }
+    };
+
+    let blinded = public_key.encrypt(&s) * ciphertext % n;
+
+    Some(oracle.decrypt(&blinded)? * s_inverse % n)
+}
+
+/// Håstad's broadcast attack: recovers a message that was encrypted without padding under k
+/// different public keys that share a small exponent e <= k. Combining the ciphertexts with the
+/// CRT gives m^e mod n_1 * ... * n_k, and since m is smaller than every n_i, that's m^e exactly, so
+/// m is its integer e-th root. Returns `None` if the keys don't share a positive exponent, there are
+/// too few of them, or the ciphertexts aren't encryptions of the same message.
+pub fn broadcast_attack(ciphertexts: &[(RsaPublicKey, BigUint)]) -> Option<BigUint> {
+    let e = ciphertexts.first()?.0.e.to_u64().and_then(|e| u32::try_from(e).ok())?;
+
+    if e == 0 || ciphertexts.len() < e as usize || ciphertexts.iter().any(|(key, _)| key.e != BigUint::from(e)) {
+        return None;
+    }
+
+    let congruences: Vec<_> = ciphertexts.iter()
+        .map(|(key, ciphertext)| (ciphertext.clone(), key.n.clone()))
+        .collect();
+
+    // Moduli that aren't coprime share a prime factor, which is a different (and easier) attack
+    let (power, _) = chinese_remainder(&congruences)?;
+
+    power.exact_nth_root(e)
+}
+
+/// The Franklin-Reiter related-message attack for e = 3: given encryptions c1 and c2 of m and of
+/// a * m + b under the same key (as with a known linear padding), recovers m. In general this
+/// takes the GCD of two polynomials mod n, but for e = 3 the GCD has a closed form:
+///
+/// ```text
+/// m = b * (c2 + 2 * a^3 * c1 - b^3) / (a * (c2 - a^3 * c1 + 2 * b^3)) mod n
+/// ```
+///
+/// Returns `None` if e isn't 3 or the denominator isn't invertible (in which case it shares a
+/// factor with n, or b is zero and the messages aren't usefully related).
+pub fn related_message_attack(
+    public_key: &RsaPublicKey,
+    a: &BigUint,
+    b: &BigUint,
+    c1: &BigUint,
+    c2: &BigUint,
+) -> Option<BigUint> {
+    if public_key.e != BigUint::from(3u8) {
+        return None;
+    }
+
+    let n = &public_key.n;
+    let three = BigUint::from(3u8);
+    let a_cubed_c1 = a.modpow(&three, n) * c1 % n;
+    let b_cubed = b.modpow(&three, n);
+
+    let numerator = b * ((c2 + (&a_cubed_c1 << 1) + n - &b_cubed) % n) % n;
+    let denominator = a * ((c2 + n - a_cubed_c1 + (b_cubed << 1)) % n) % n;
+
+    Some(numerator * denominator.modinv(n)? % n)
+}
+
+// Numbers are written as a 32-bit little-endian length followed by their big-endian bytes
+fn write_biguint(writer: &mut impl Write, value: &BigUint) -> io::Result<()> {
+    let bytes = value.to_bytes_be();
+
+    writer.write_all(&(bytes.len() as u32).to_le_bytes())?;
+    writer.write_all(&bytes)
+}
+
+fn read_biguint(reader: &mut impl Read) -> io::Result<BigUint> {
+    let mut length = [0; 4];
+    reader.read_exact(&mut length)?;
+
+    let length = u32::from_le_bytes(length) as usize;
+
+    if length > MAX_SERIALIZED_BYTES {
+        return Err(io::Error::new(ErrorKind::InvalidData, "Number is too large"));
+    }
+
+    let mut bytes = vec![0; length];
+    reader.read_exact(&mut bytes)?;
+
+    Ok(BigUint::from_bytes_be(&bytes))
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::ErrorKind;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+    use crate::bigint::BigUint;
+    use crate::rsa::{
+        broadcast_attack, related_message_attack, unpadded_message_recovery, write_biguint, DecryptionOracle, RsaPrivateKey,
+        RsaPublicKey,
+    };
+
+    #[test]
+    fn test_textbook_example() {
+        // From Wikipedia's RSA article
+        let key = RsaPrivateKey::from_primes(BigUint::from(61u8), BigUint::from(53u8), BigUint::from(17u8)).unwrap();
+
+        assert_eq!(&BigUint::from(3233u16), key.public_key().n());
+        assert_eq!(&BigUint::from(2753u16), key.d());
+        assert_eq!(BigUint::from(2790u16), key.public_key().encrypt(&BigUint::from(65u8)));
+        assert_eq!(BigUint::from(65u8), key.decrypt(&BigUint::from(2790u16)));
+
+        // e = 3 isn't invertible mod (61 - 1)(53 - 1)
+        assert_eq!(None, RsaPrivateKey::from_primes(BigUint::from(61u8), BigUint::from(53u8), BigUint::from(3u8)));
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let mut rng = StdRng::seed_from_u64(39);
+
+        for (bits, e) in [(512, 65537u32), (768, 3), (1024, 65537), (2048, 65537)] {
+            let key = RsaPrivateKey::generate(&mut rng, bits, &BigUint::from(e));
+            let public_key = key.public_key();
+
+            assert_eq!(bits, public_key.bits());
+            assert_eq!(&BigUint::from(e), public_key.e());
+
+            for _ in 0..4 {
+                let message = BigUint::random_below(&mut rng, public_key.n());
+                let ciphertext = public_key.encrypt(&message);
+
+                assert_eq!(message, key.decrypt(&ciphertext));
+                assert_eq!(key.decrypt(&ciphertext), key.decrypt_without_crt(&ciphertext));
+
+                let signature = key.sign(&message);
+
+                assert_eq!(signature, key.decrypt_without_crt(&message));
+                assert!(public_key.verify(&message, &signature));
+                assert!(!public_key.verify(&(&message + BigUint::one()), &signature));
+            }
+        }
+    }
+
+    #[test]
+    fn test_serialization() {
+        let key = RsaPrivateKey::generate(&mut StdRng::seed_from_u64(40), 512, &BigUint::from(65537u32));
+
+        let mut bytes = vec![];
+        key.write_to(&mut bytes).unwrap();
+
+        assert_eq!(key, RsaPrivateKey::read_from(&mut bytes.as_slice()).unwrap());
+        assert_eq!(key.public_key(), &RsaPublicKey::read_from(&mut bytes.as_slice()).unwrap());
+
+        // Truncated keys, and keys whose d or q have been tampered with, are rejected
+        assert!(RsaPrivateKey::read_from(&mut &bytes[..bytes.len() - 1]).is_err());
+
+        let public_key_length = 8 + key.public_key().n().to_bytes_be().len() + key.public_key().e().to_bytes_be().len();
+
+        for index in [public_key_length + 4, bytes.len() - 1] {
+            let mut corrupted = bytes.clone();
+            corrupted[index] ^= 2;
+
+            assert!(RsaPrivateKey::read_from(&mut corrupted.as_slice()).is_err());
+        }
+
+        // So are keys whose "primes" are 1 and n
+        let mut trivial = vec![];
+        key.public_key().write_to(&mut trivial).unwrap();
+
+        for value in [key.d(), &BigUint::one(), key.public_key().n()] {
+            write_biguint(&mut trivial, value).unwrap();
+        }
+
+        assert_eq!(ErrorKind::InvalidData, RsaPrivateKey::read_from(&mut trivial.as_slice()).unwrap_err().kind());
+        assert_eq!(None, RsaPrivateKey::from_primes(BigUint::one(), key.public_key().n().clone(), BigUint::from(65537u32)));
+    }
+
+    #[test]
+    fn test_broadcast_attack() {
+        let mut rng = StdRng::seed_from_u64(40);
+
+        for (e, bits) in [(3u8, 512), (5, 256)] {
+            let keys: Vec<_> = (0..e).map(|_| RsaPrivateKey::generate(&mut rng, bits, &BigUint::from(e))).collect();
+            let smallest_n = keys.iter().map(|key| key.public_key().n()).min().unwrap();
+            let message = BigUint::random_below(&mut rng, smallest_n);
+
+            let ciphertexts: Vec<_> = keys.iter()
+                .map(|key| (key.public_key().clone(), key.public_key().encrypt(&message)))
+                .collect();
+
+            assert_eq!(Some(&message), broadcast_attack(&ciphertexts).as_ref());
+
+            // One ciphertext short is refused outright
+            assert_eq!(None, broadcast_attack(&ciphertexts[1..]));
+
+            // A "message" larger than every modulus has m^e larger than their product, so the CRT
+            // result wraps around and isn't an e-th power
+            let largest_n = keys.iter().map(|key| key.public_key().n()).max().unwrap();
+            let oversized = largest_n + BigUint::random_below(&mut rng, smallest_n);
+
+            let ciphertexts: Vec<_> = keys.iter()
+                .map(|key| (key.public_key().clone(), oversized.modpow(key.public_key().e(), key.public_key().n())))
+                .collect();
+
+            assert_eq!(None, broadcast_attack(&ciphertexts));
+        }
+
+        let key = RsaPublicKey::new(BigUint::from(3233u32), BigUint::zero());
+        assert_eq!(None, broadcast_attack(&[(key, BigUint::one())]));
+    }
+
+    #[test]
+    fn test_related_message_attack() {
+        let mut rng = StdRng::seed_from_u64(41);
+        let key = RsaPrivateKey::generate(&mut rng, 1024, &BigUint::from(3u8));
+        let public_key = key.public_key();
+
+        for _ in 0..4 {
+            let message = BigUint::random_below(&mut rng, public_key.n());
+            let a = BigUint::random_below(&mut rng, public_key.n());
+            let b = BigUint::random_below(&mut rng, public_key.n());
+            let related = (&a * &message + &b) % public_key.n();
+
+            let c1 = public_key.encrypt(&message);
+            let c2 = public_key.encrypt(&related);
+
+            assert_eq!(Some(&message), related_message_attack(public_key, &a, &b, &c1, &c2).as_ref());
+        }
+
+        // A message padded by appending a known byte: m' = 256 * m + 0x42
+        let message = BigUint::from_bytes_be(b"Attack at dawn");
+        let c1 = public_key.encrypt(&message);
+        let c2 = public_key.encrypt(&(&message * BigUint::from(256u16) + BigUint::from(0x42u8)));
+
+        assert_eq!(
+            Some(message),
+            related_message_attack(public_key, &BigUint::from(256u16), &BigUint::from(0x42u8), &c1, &c2)
+        );
+    }
+
+    #[test]
+    fn test_unpadded_message_recovery() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut oracle = DecryptionOracle::new(RsaPrivateKey::generate(&mut rng, 1024, &BigUint::from(65537u32)));
+
+        let message = BigUint::from_bytes_be(br#"{"time": 1356304276, "social": "555-55-5555"}"#);
+        let ciphertext = oracle.public_key().encrypt(&message);
+
+        // The victim's request goes through, after which the ciphertext can't be replayed
+        assert_eq!(Some(&message), oracle.decrypt(&ciphertext).as_ref());
+        assert_eq!(None, oracle.decrypt(&ciphertext));
+        assert_eq!(None, oracle.decrypt(&(&ciphertext + oracle.public_key().n())));
+
+        assert_eq!(Some(message), unpadded_message_recovery(&mut oracle, &ciphertext, &mut rng));
+    }
+}
@@ -0,0 +1,142 @@
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use crate::random::MersenneTwister;
+
+/// A set of 32-bit seeds to search.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum SeedSpace {
+    /// The seeds from `latest - before` to `latest`, inclusive. Timestamps truncated to 32 bits
+    /// wrap around every so often, so windows do too.
+    Window { latest: u32, before: u32 },
+
+    /// Every 32-bit seed, for when there's no telling when the generator was seeded
+    Full,
+}
+
+impl SeedSpace {
+    /// Returns the window of millisecond timestamps (truncated to 32 bits) up to `max_age` old.
+    pub fn recent_millis(max_age: Duration) -> Self {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_millis();
+
+        SeedSpace::Window {
+            latest: now as u32,
+            before: max_age.as_millis().min(u32::MAX as u128) as u32,
+        }
+    }
+
+    /// Returns the window of Unix timestamps (in seconds, like C's `time(NULL)`) up to `max_age`
+    /// old.
+    pub fn recent_seconds(max_age: Duration) -> Self {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+
+        SeedSpace::Window {
+            latest: now as u32,
+            before: max_age.as_secs().min(u32::MAX as u64) as u32,
+        }
+    }
+
+    /// Returns the most recent seed in the space and the number of seeds in the space.
+    fn bounds(&self) -> (u32, u64) {
+        match self {
+            SeedSpace::Window { latest, before } => (*latest, *before as u64 + 1),
+            SeedSpace::Full => (u32::MAX, 1 << u32::BITS),
+        }
+    }
+}
+
+/// Returns every seed in the given space for which `derive`, applied to a freshly-seeded
+/// [MersenneTwister], reproduces `observed` (e.g. a password reset token built from the
+/// generator's first few outputs). Seeds are returned from most to least recent.
+pub fn find_seeds<T: PartialEq + Sync>(
+    observed: &T,
+    space: SeedSpace,
+    derive: impl Fn(&mut MersenneTwister) -> T + Sync,
+) -> Vec<u32> {
+    parallel_search(space, |seed| derive(&mut MersenneTwister::new(seed)) == *observed)
+}
+
+/// Returns every seed in the given space whose generator starts with the given outputs, from
+/// most to least recent. Almost every candidate is rejected by [MersenneTwister::first_output]
+/// without seeding a whole generator, which makes searching the full 32-bit space practical.
+pub fn find_seeds_from_outputs(outputs: &[u32], space: SeedSpace) -> Vec<u32> {
+    assert!(!outputs.is_empty());
+
+    parallel_search(space, |seed| {
+        if MersenneTwister::first_output(seed) != outputs[0] {
+            return false;
+        }
+
+        let mut mt = MersenneTwister::new(seed);
+        outputs.iter().all(|&output| mt.next_u32() == output)
+    })
+}
+
+/// Returns every seed in the given space that satisfies `is_match`, from most to least recent,
+/// splitting the space evenly across all available cores.
+pub fn parallel_search(space: SeedSpace, is_match: impl Fn(u32) -> bool + Sync) -> Vec<u32> {
+    let (latest, count) = space.bounds();
+    let thread_count = thread::available_parallelism().map_or(1, |n| n.get()) as u64;
+
+    thread::scope(|scope| {
+        // Each thread takes a contiguous run of offsets from the latest seed, so concatenating
+        // their results keeps everything in order
+        let handles: Vec<_> = (0..thread_count)
+            .map(|t| {
+                let is_match = &is_match;
+                let offsets = count * t / thread_count..count * (t + 1) / thread_count;
+
+                scope.spawn(move || {
+                    offsets
+                        .map(|offset| latest.wrapping_sub(offset as u32))
+                        .filter(|&seed| is_match(seed))
+                        .collect::<Vec<u32>>()
+                })
+            })
+            .collect();
+
+        handles.into_iter()
+            .flat_map(|handle| handle.join().unwrap())
+            .collect()
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use rand::{Rng, RngCore};
+    use crate::seed_search::{find_seeds, find_seeds_from_outputs, SeedSpace};
+    use crate::random::MersenneTwister;
+
+    #[test]
+    fn test_find_seeds_from_outputs() {
+        let latest = rand::thread_rng().next_u32();
+        let seed = latest.wrapping_sub(rand::thread_rng().gen_range(0..10_000));
+
+        let mut mt = MersenneTwister::new(seed);
+        let outputs = [mt.next_u32(), mt.next_u32()];
+
+        let space = SeedSpace::Window { latest, before: 10_000 };
+
+        assert_eq!(vec![seed], find_seeds_from_outputs(&outputs, space));
+        assert_eq!(vec![seed], find_seeds_from_outputs(&outputs[..1], space));
+    }
+
+    #[test]
+    fn test_find_seeds() {
+        // Windows that cross zero wrap around to the top of the seed space
+        let space = SeedSpace::Window { latest: 1_000, before: 2_000 };
+
+        let token = |mt: &mut MersenneTwister| format!("{:08x}{:08x}", mt.next_u32(), mt.next_u32());
+
+        for seed in [0, 1_000, u32::MAX - 999] {
+            assert_eq!(vec![seed], find_seeds(&token(&mut MersenneTwister::new(seed)), space, token));
+        }
+
+        assert!(find_seeds(&token(&mut MersenneTwister::new(u32::MAX - 1_000)), space, token).is_empty());
+    }
+}
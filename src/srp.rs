@@ -0,0 +1,691 @@
+use std::collections::HashMap;
+use std::io::{self, BufRead, BufReader, ErrorKind, Read, Write};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+use lazy_static::lazy_static;
+use rand::Rng;
+use crate::bigint::BigUint;
+use crate::dh::DhGroup;
+use crate::hmac::{hmac_sha1, verify_hmac_sha1};
+use crate::sha1::Sha1;
+
+const SALT_LENGTH: usize = 16;
+
+lazy_static! {
+    static ref STANDARD: SrpParameters = SrpParameters::new(DhGroup::modp_1536().p.clone(), BigUint::from(2u8));
+}
+
+/// SRP-6a group parameters: a safe prime `n`, a generator `g`, and the multiplier
+/// k = H(N | PAD(g)) from RFC 5054.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SrpParameters {
+    pub n: BigUint,
+    pub g: BigUint,
+    pub k: BigUint,
+}
+
+impl SrpParameters {
+    pub fn new(n: BigUint, g: BigUint) -> Self {
+        let padded_g = pad(&g, &n);
+        let k = hash_to_int(&[&n.to_bytes_be(), &padded_g]);
+
+        Self { n, g, k }
+    }
+
+    /// The 1536-bit NIST prime (as used in the Diffie-Hellman challenges) with g = 2.
+    pub fn standard() -> &'static Self {
+        &STANDARD
+    }
+
+    /// Returns u = H(PAD(A) | PAD(B)), which binds the session to both public keys.
+    pub fn scrambler(&self, client_public_key: &BigUint, server_public_key: &BigUint) -> BigUint {
+        hash_to_int(&[&pad(client_public_key, &self.n), &pad(server_public_key, &self.n)])
+    }
+
+    fn random_exponent(&self, rng: &mut impl Rng) -> BigUint {
+        BigUint::random_below(rng, &(&self.n - BigUint::one())) + BigUint::one()
+    }
+}
+
+// Left-pads a value with zeros to the length of the modulus
+fn pad(value: &BigUint, n: &BigUint) -> Vec<u8> {
+    let bytes = value.to_bytes_be();
+    let length = n.bits().div_ceil(8) as usize;
+
+    let mut padded = vec![0; length.saturating_sub(bytes.len())];
+    padded.extend_from_slice(&bytes);
+
+    padded
+}
+
+fn hash(parts: &[&[u8]]) -> Vec<u8> {
+    let mut sha1 = Sha1::default();
+
+    for part in parts {
+        sha1.update(part);
+    }
+
+    sha1.finish()
+}
+
+fn hash_to_int(parts: &[&[u8]]) -> BigUint {
+    BigUint::from_bytes_be(&hash(parts))
+}
+
+/// Returns the private key x = H(s | H(I | ":" | P)) derived from a user's password.
+pub fn private_key(salt: &[u8], username: &str, password: &str) -> BigUint {
+    let identity_hash = hash(&[username.as_bytes(), b":", password.as_bytes()]);
+
+    hash_to_int(&[salt, &identity_hash])
+}
+
+/// Returns the proof of a session key that the client sends the server: HMAC-SHA1(K, salt).
+pub fn proof(session_key: &[u8], salt: &[u8]) -> Vec<u8> {
+    hmac_sha1(session_key, salt)
+}
+
+pub fn verify_proof(session_key: &[u8], salt: &[u8], proof: &[u8]) -> bool {
+    verify_hmac_sha1(session_key, salt, proof)
+}
+
+/// What the server stores for each user in place of their password: a salt and the verifier
+/// v = g^x mod N.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Registration {
+    pub salt: Vec<u8>,
+    pub verifier: BigUint,
+}
+
+impl Registration {
+    pub fn new(parameters: &SrpParameters, rng: &mut impl Rng, username: &str, password: &str) -> Self {
+        let mut salt = vec![0; SALT_LENGTH];
+        rng.fill_bytes(&mut salt);
+
+        let x = private_key(&salt, username, password);
+
+        Self {
+            verifier: parameters.g.modpow(&x, &parameters.n),
+            salt,
+        }
+    }
+}
+
+/// The client's half of a login: an ephemeral private key a and its public key A = g^a mod N.
+pub struct ClientHandshake {
+    private_key: BigUint,
+    public_key: BigUint,
+}
+
+impl ClientHandshake {
+    pub fn new(parameters: &SrpParameters, rng: &mut impl Rng) -> Self {
+        let private_key = parameters.random_exponent(rng);
+
+        Self {
+            public_key: parameters.g.modpow(&private_key, &parameters.n),
+            private_key,
+        }
+    }
+
+    pub fn public_key(&self) -> &BigUint {
+        &self.public_key
+    }
+
+    /// Returns the session key K = H(S), where S = (B - k * g^x)^(a + u * x) mod N, or `None` if
+    /// the server's public key is zero mod N (or u is zero), as SRP-6a requires.
+    pub fn session_key(
+        &self,
+        parameters: &SrpParameters,
+        username: &str,
+        password: &str,
+        salt: &[u8],
+        server_public_key: &BigUint,
+    ) -> Option<Vec<u8>> {
+        let n = &parameters.n;
+
+        if (server_public_key % n).is_zero() {
+            return None;
+        }
+
+        let u = parameters.scrambler(&self.public_key, server_public_key);
+
+        if u.is_zero() {
+            return None;
+        }
+
+        let x = private_key(salt, username, password);
+        let k_v = &parameters.k * parameters.g.modpow(&x, n) % n;
+        let base = (server_public_key % n + n - k_v) % n;
+        let secret = base.modpow(&(&self.private_key + u * x), n);
+
+        Some(hash(&[&secret.to_bytes_be()]))
+    }
+}
+
+/// The server's half of a login: an ephemeral private key b and its public key
+/// B = k * v + g^b mod N.
+pub struct ServerHandshake {
+    private_key: BigUint,
+    public_key: BigUint,
+    verifier: BigUint,
+}
+
+impl ServerHandshake {
+    pub fn new(parameters: &SrpParameters, rng: &mut impl Rng, registration: &Registration) -> Self {
+        let n = &parameters.n;
+        let private_key = parameters.random_exponent(rng);
+
+        Self {
+            public_key: (&parameters.k * &registration.verifier + parameters.g.modpow(&private_key, n)) % n,
+            private_key,
+            verifier: registration.verifier.clone(),
+        }
+    }
+
+    pub fn public_key(&self) -> &BigUint {
+        &self.public_key
+    }
+
+    /// Returns the session key K = H(S), where S = (A * v^u)^b mod N, or `None` if the client's
+    /// public key is zero mod N (which would force S to zero).
+    pub fn session_key(&self, parameters: &SrpParameters, client_public_key: &BigUint) -> Option<Vec<u8>> {
+        if (client_public_key % &parameters.n).is_zero() {
+            return None;
+        }
+
+        Some(self.session_key_unchecked(parameters, client_public_key))
+    }
+
+    /// Like [ServerHandshake::session_key], but without rejecting client keys that are zero mod N
+    /// (as servers that skip the check do). See [zero_key_login].
+    pub fn session_key_unchecked(&self, parameters: &SrpParameters, client_public_key: &BigUint) -> Vec<u8> {
+        let n = &parameters.n;
+        let u = parameters.scrambler(client_public_key, &self.public_key);
+        let base = client_public_key * self.verifier.modpow(&u, n) % n;
+        let secret = base.modpow(&self.private_key, n);
+
+        hash(&[&secret.to_bytes_be()])
+    }
+}
+
+/// A login server holding the registrations of its users. Logins run over any byte stream (like a
+/// `TcpStream`) as a line-based exchange of hex values (the username is hex-encoded too, so it can
+/// contain spaces and newlines):
+///
+/// ```text
+/// C -> S: username A
+/// S -> C: salt B          (or FAIL for an unknown user)
+/// C -> S: HMAC-SHA1(K, salt)
+/// S -> C: OK              (or FAIL)
+/// ```
+pub struct Server {
+    parameters: SrpParameters,
+    users: HashMap<String, Registration>,
+    checks_client_keys: bool,
+}
+
+impl Server {
+    pub fn new(parameters: SrpParameters) -> Self {
+        Self { parameters, users: HashMap::new(), checks_client_keys: true }
+    }
+
+    /// Returns a server that accepts client keys that are zero mod N, and so is open to
+    /// [zero_key_login].
+    pub fn without_key_checks(parameters: SrpParameters) -> Self {
+        Self { checks_client_keys: false, ..Self::new(parameters) }
+    }
+
+    pub fn parameters(&self) -> &SrpParameters {
+        &self.parameters
+    }
+
+    pub fn register(&mut self, username: &str, password: &str) {
+        let registration = Registration::new(&self.parameters, &mut rand::thread_rng(), username, password);
+        self.users.insert(username.to_string(), registration);
+    }
+
+    /// Runs one login over the stream, and returns whether the client proved it knew the password.
+    pub fn handle(&self, stream: impl Read + Write) -> io::Result<bool> {
+        self.run(stream, |registration, client_public_key| {
+            let handshake = ServerHandshake::new(&self.parameters, &mut rand::thread_rng(), registration);
+
+            let key = if self.checks_client_keys {
+                handshake.session_key(&self.parameters, client_public_key)
+            } else {
+                Some(handshake.session_key_unchecked(&self.parameters, client_public_key))
+            };
+
+            (format!("{:x}", handshake.public_key()), key)
+        })
+    }
+
+    // Runs the server side of a login. `challenge` returns what to send after the salt, and the
+    // session key the client has to prove it knows (or `None` to refuse the login).
+    fn run(
+        &self,
+        stream: impl Read + Write,
+        challenge: impl FnOnce(&Registration, &BigUint) -> (String, Option<Vec<u8>>),
+    ) -> io::Result<bool> {
+        let mut stream = BufReader::new(stream);
+        let (username, client_public_key) = read_login_request(&mut stream)?;
+
+        let Some(registration) = self.users.get(&username) else {
+            writeln!(stream.get_mut(), "FAIL")?;
+            return Ok(false);
+        };
+
+        let (fields, key) = challenge(registration, &client_public_key);
+        writeln!(stream.get_mut(), "{} {}", hex::encode(&registration.salt), fields)?;
+
+        let client_proof = parse_bytes(&read_line(&mut stream)?)?;
+        let authenticated = key.is_some_and(|key| verify_proof(&key, &registration.salt, &client_proof));
+
+        writeln!(stream.get_mut(), "{}", if authenticated { "OK" } else { "FAIL" })?;
+
+        Ok(authenticated)
+    }
+}
+
+/// Logs in to a [Server] over the stream, and returns whether the server accepted the password.
+pub fn login(parameters: &SrpParameters, stream: impl Read + Write, username: &str, password: &str) -> io::Result<bool> {
+    let handshake = ClientHandshake::new(parameters, &mut rand::thread_rng());
+
+    run_client(stream, username, handshake.public_key(), |salt, server_public_key, extra_fields| {
+        let [] = parse_ints(extra_fields)?;
+
+        handshake.session_key(parameters, username, password, salt, server_public_key)
+            .ok_or_else(|| invalid_data("Invalid server key"))
+    })
+}
+
+/// The zero-key attack: logs in as any user without their password by sending a client public key
+/// A = multiple * N. A server that doesn't reject it computes S = (A * v^u)^b = 0 mod N, so the
+/// session key is just H(0). Returns whether the server accepted the login.
+pub fn zero_key_login(parameters: &SrpParameters, stream: impl Read + Write, username: &str, multiple: u32) -> io::Result<bool> {
+    let client_public_key = &parameters.n * BigUint::from(multiple);
+    let key = hash(&[&BigUint::zero().to_bytes_be()]);
+
+    run_client(stream, username, &client_public_key, |_, _, _| Ok(key))
+}
+
+// Runs the client side of a login. `session_key` computes the session key from the salt, the
+// server's public key and any further fields the server sent (which it has to parse itself).
+fn run_client(
+    stream: impl Read + Write,
+    username: &str,
+    client_public_key: &BigUint,
+    session_key: impl FnOnce(&[u8], &BigUint, &[&str]) -> io::Result<Vec<u8>>,
+) -> io::Result<bool> {
+    let mut stream = BufReader::new(stream);
+
+    writeln!(stream.get_mut(), "{} {:x}", hex::encode(username), client_public_key)?;
+
+    let line = read_line(&mut stream)?;
+
+    if line == "FAIL" {
+        return Ok(false);
+    }
+
+    let fields: Vec<&str> = line.split(' ').collect();
+
+    let [salt, server_public_key, extra_fields @ ..] = &fields[..] else {
+        return Err(invalid_data("Expected a salt and key"));
+    };
+
+    let salt = parse_bytes(salt)?;
+    let key = session_key(&salt, &parse_int(server_public_key)?, extra_fields)?;
+
+    writeln!(stream.get_mut(), "{}", hex::encode(proof(&key, &salt)))?;
+
+    Ok(read_line(&mut stream)? == "OK")
+}
+
+/// A server for the simplified SRP variant from cryptopals, which drops the multiplier and has the
+/// server pick u at random: B = g^b, S = (A * v^u)^b. Nothing the server sends depends on the
+/// password, so a fake server can collect a proof to attack offline (see [capture_simplified_login]).
+/// The exchange is the same as for [Server], except that the server sends `salt B u`.
+pub struct SimplifiedServer {
+    // Registration and the shape of the exchange are the same as for the full protocol
+    server: Server,
+}
+
+impl SimplifiedServer {
+    pub fn new(parameters: SrpParameters) -> Self {
+        Self { server: Server::new(parameters) }
+    }
+
+    pub fn register(&mut self, username: &str, password: &str) {
+        self.server.register(username, password);
+    }
+
+    /// Runs one login over the stream, and returns whether the client proved it knew the password.
+    pub fn handle(&self, stream: impl Read + Write) -> io::Result<bool> {
+        let parameters = &self.server.parameters;
+        let n = &parameters.n;
+
+        self.server.run(stream, |registration, client_public_key| {
+            let mut rng = rand::thread_rng();
+            let private_key = parameters.random_exponent(&mut rng);
+            let u = BigUint::random_bits(&mut rng, 128);
+
+            let key = (!(client_public_key % n).is_zero()).then(|| {
+                let secret = (client_public_key * registration.verifier.modpow(&u, n) % n).modpow(&private_key, n);
+                hash(&[&secret.to_bytes_be()])
+            });
+
+            (format!("{:x} {:x}", parameters.g.modpow(&private_key, n), u), key)
+        })
+    }
+}
+
+/// Logs in to a [SimplifiedServer] over the stream, and returns whether the server accepted the
+/// password.
+pub fn simplified_login(parameters: &SrpParameters, stream: impl Read + Write, username: &str, password: &str) -> io::Result<bool> {
+    let handshake = ClientHandshake::new(parameters, &mut rand::thread_rng());
+
+    run_client(stream, username, handshake.public_key(), |salt, server_public_key, extra_fields| {
+        let [u] = parse_ints(extra_fields)?;
+        let x = private_key(salt, username, password);
+        let secret = server_public_key.modpow(&(&handshake.private_key + u * x), &parameters.n);
+
+        Ok(hash(&[&secret.to_bytes_be()]))
+    })
+}
+
+/// What a fake [SimplifiedServer] learns from a client's login attempt.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CapturedLogin {
+    pub username: String,
+    pub salt: Vec<u8>,
+    pub client_public_key: BigUint,
+    pub proof: Vec<u8>,
+}
+
+/// Plays a simplified SRP server without knowing the user's password, choosing b = 1 and u = 1 so
+/// that S = A * g^x mod N. The client's proof can then be checked against any password guess
+/// offline (see [CapturedLogin::crack]). The login is always refused.
+pub fn capture_simplified_login(parameters: &SrpParameters, stream: impl Read + Write) -> io::Result<CapturedLogin> {
+    let mut stream = BufReader::new(stream);
+    let (username, client_public_key) = read_login_request(&mut stream)?;
+
+    let mut salt = vec![0; SALT_LENGTH];
+    rand::thread_rng().fill(&mut salt[..]);
+
+    writeln!(stream.get_mut(), "{} {:x} 1", hex::encode(&salt), parameters.g)?;
+
+    let proof = parse_bytes(&read_line(&mut stream)?)?;
+    writeln!(stream.get_mut(), "FAIL")?;
+
+    Ok(CapturedLogin { username, salt, client_public_key, proof })
+}
+
+/// The outcome of an offline dictionary attack.
+#[derive(Clone, Debug)]
+pub struct DictionaryAttack {
+    pub password: Option<String>,
+    pub guesses: usize,
+    pub elapsed: Duration,
+}
+
+impl DictionaryAttack {
+    pub fn guesses_per_second(&self) -> f64 {
+        self.guesses as f64 / self.elapsed.as_secs_f64()
+    }
+}
+
+impl CapturedLogin {
+    /// Returns whether the captured proof matches the given password.
+    pub fn matches(&self, parameters: &SrpParameters, password: &str) -> bool {
+        let n = &parameters.n;
+        let x = private_key(&self.salt, &self.username, password);
+        let secret = &self.client_public_key * parameters.g.modpow(&x, n) % n;
+
+        verify_proof(&hash(&[&secret.to_bytes_be()]), &self.salt, &self.proof)
+    }
+
+    /// Tries every word as the password, spread across all available cores.
+    pub fn crack(&self, parameters: &SrpParameters, words: &[String]) -> DictionaryAttack {
+        let start = Instant::now();
+        let found = AtomicBool::new(false);
+        let guesses = AtomicUsize::new(0);
+
+        let threads = thread::available_parallelism().map_or(1, usize::from);
+        let chunk_size = words.len().div_ceil(threads).max(1);
+
+        let password = thread::scope(|scope| {
+            let handles: Vec<_> = words.chunks(chunk_size)
+                .map(|chunk| {
+                    let (found, guesses) = (&found, &guesses);
+
+                    scope.spawn(move || {
+                        for word in chunk {
+                            if found.load(Ordering::Relaxed) {
+                                break;
+                            }
+
+                            guesses.fetch_add(1, Ordering::Relaxed);
+
+                            if self.matches(parameters, word) {
+                                found.store(true, Ordering::Relaxed);
+                                return Some(word.clone());
+                            }
+                        }
+
+                        None
+                    })
+                })
+                .collect();
+
+            handles.into_iter().find_map(|handle| handle.join().unwrap())
+        });
+
+        DictionaryAttack {
+            password,
+            guesses: guesses.into_inner(),
+            elapsed: start.elapsed(),
+        }
+    }
+}
+
+fn read_line(reader: &mut impl BufRead) -> io::Result<String> {
+    let mut line = String::new();
+
+    if reader.read_line(&mut line)? == 0 {
+        return Err(io::Error::new(ErrorKind::UnexpectedEof, "Connection closed"));
+    }
+
+    Ok(line.trim_end().to_string())
+}
+
+// Reads the first line of a login, `username A`
+fn read_login_request(reader: &mut impl BufRead) -> io::Result<(String, BigUint)> {
+    let line = read_line(reader)?;
+    let (username, client_public_key) = line.split_once(' ').ok_or_else(|| invalid_data("Expected a username and key"))?;
+    let username = String::from_utf8(parse_bytes(username)?).map_err(|_| invalid_data("Invalid username"))?;
+
+    Ok((username, parse_int(client_public_key)?))
+}
+
+fn parse_int(hex: &str) -> io::Result<BigUint> {
+    BigUint::from_hex(hex).map_err(|_| invalid_data("Invalid number"))
+}
+
+fn parse_ints<const N: usize>(fields: &[&str]) -> io::Result<[BigUint; N]> {
+    let values = fields.iter().map(|field| parse_int(field)).collect::<io::Result<Vec<_>>>()?;
+
+    values.try_into().map_err(|_| invalid_data("Unexpected number of fields"))
+}
+
+fn parse_bytes(hex: &str) -> io::Result<Vec<u8>> {
+    hex::decode(hex).map_err(|_| invalid_data("Invalid hex"))
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(ErrorKind::InvalidData, message)
+}
+
+#[cfg(test)]
+mod test {
+    use std::net::{TcpListener, TcpStream};
+    use std::thread;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+    use crate::bigint::BigUint;
+    use crate::srp::{
+        capture_simplified_login, login, simplified_login, zero_key_login, ClientHandshake, Registration, Server,
+        ServerHandshake, SimplifiedServer, SrpParameters,
+    };
+    use crate::text::WordList;
+
+    #[test]
+    fn test_handshake() {
+        let parameters = SrpParameters::standard();
+        let mut rng = StdRng::seed_from_u64(36);
+        let registration = Registration::new(parameters, &mut rng, "alice@example.com", "hunter2");
+
+        let client = ClientHandshake::new(parameters, &mut rng);
+        let server = ServerHandshake::new(parameters, &mut rng, &registration);
+
+        let server_key = server.session_key(parameters, client.public_key()).unwrap();
+
+        assert_eq!(
+            Some(&server_key),
+            client.session_key(parameters, "alice@example.com", "hunter2", &registration.salt, server.public_key()).as_ref()
+        );
+        assert_ne!(
+            Some(&server_key),
+            client.session_key(parameters, "alice@example.com", "hunter3", &registration.salt, server.public_key()).as_ref()
+        );
+
+        // Keys that are zero mod N are refused by both sides
+        for bad_key in [BigUint::zero(), parameters.n.clone(), &parameters.n * BigUint::from(2u8)] {
+            assert_eq!(None, server.session_key(parameters, &bad_key));
+            assert_eq!(None, client.session_key(parameters, "alice@example.com", "hunter2", &registration.salt, &bad_key));
+        }
+    }
+
+    #[test]
+    fn test_multiplier() {
+        // k = H(N | PAD(g)) for the RFC 5054 1024-bit group, from its test vectors
+        let parameters = SrpParameters::new(
+            BigUint::from_hex("
+                EEAF0AB9ADB38DD69C33F80AFA8FC5E86072618775FF3C0B9EA2314C9C256576D674DF7496EA81D3383B4813D692C6E0E0D5D8E250B98BE4
+                8E495C1D6089DAD15DC7D7B46154D6B6CE8EF4AD69B15D4982559B297BCF1885C529F566660E57EC68EDBC3C05726CC02FD4CBF4976EAA9A
+                FD5138FE8376435B9FC61D2FC0EB06E3
+            ").unwrap(),
+            BigUint::from(2u8),
+        );
+
+        assert_eq!(BigUint::from_hex("7556AA045AEF2CDD07ABAF0F665C3E818913186F").unwrap(), parameters.k);
+    }
+
+    #[test]
+    fn test_login_over_tcp() {
+        let mut server = Server::new(SrpParameters::standard().clone());
+        server.register("alice@example.com", "hunter2");
+        server.register("carol smith\nadmin", "hunter2");
+        server.register("carol", "hunter4");
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+
+        let attempts = [
+            ("alice@example.com", "hunter2", true),
+            ("alice@example.com", "hunter3", false),
+            ("bob@example.com", "hunter2", false),
+            // Usernames are hex-encoded on the wire, so neither half of this one gets mistaken for
+            // a separate field or user
+            ("carol smith\nadmin", "hunter2", true),
+            ("carol", "hunter2", false),
+        ];
+
+        thread::scope(|scope| {
+            let server = &server;
+
+            scope.spawn(move || {
+                // Zipped this way round so the server doesn't wait for a connection after the last
+                for ((_, _, expected), stream) in attempts.into_iter().zip(listener.incoming()) {
+                    assert_eq!(expected, server.handle(stream.unwrap()).unwrap());
+                }
+            });
+
+            for (username, password, expected) in attempts {
+                let stream = TcpStream::connect(address).unwrap();
+                assert_eq!(expected, login(SrpParameters::standard(), stream, username, password).unwrap());
+            }
+        });
+    }
+
+    #[test]
+    fn test_zero_key_login() {
+        let parameters = SrpParameters::standard();
+
+        let mut vulnerable = Server::without_key_checks(parameters.clone());
+        vulnerable.register("alice@example.com", "hunter2");
+
+        let mut checked = Server::new(parameters.clone());
+        checked.register("alice@example.com", "hunter2");
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+
+        thread::scope(|scope| {
+            let (vulnerable, checked) = (&vulnerable, &checked);
+
+            scope.spawn(move || {
+                for (i, stream) in (0..6).zip(listener.incoming()) {
+                    let server = if i < 3 { vulnerable } else { checked };
+                    server.handle(stream.unwrap()).unwrap();
+                }
+            });
+
+            for expected in [true, false] {
+                for multiple in 0..3 {
+                    let stream = TcpStream::connect(address).unwrap();
+                    assert_eq!(expected, zero_key_login(parameters, stream, "alice@example.com", multiple).unwrap());
+                }
+            }
+        });
+    }
+
+    #[test]
+    fn test_simplified_srp() {
+        let parameters = SrpParameters::standard();
+        let mut rng = StdRng::seed_from_u64(38);
+        let password = WordList::bundled().random_word(&mut rng);
+
+        let mut server = SimplifiedServer::new(parameters.clone());
+        server.register("alice@example.com", password);
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+
+        let captured = thread::scope(|scope| {
+            let server = &server;
+
+            let handle = scope.spawn(move || {
+                let mut incoming = listener.incoming();
+
+                assert!(server.handle(incoming.next().unwrap().unwrap()).unwrap());
+                assert!(!server.handle(incoming.next().unwrap().unwrap()).unwrap());
+
+                capture_simplified_login(parameters, incoming.next().unwrap().unwrap()).unwrap()
+            });
+
+            for (password, expected) in [(password, true), ("not the password", false), (password, false)] {
+                let stream = TcpStream::connect(address).unwrap();
+                assert_eq!(expected, simplified_login(parameters, stream, "alice@example.com", password).unwrap());
+            }
+
+            handle.join().unwrap()
+        });
+
+        assert_eq!("alice@example.com", captured.username);
+        assert!(captured.matches(parameters, password));
+
+        let attack = captured.crack(parameters, WordList::bundled().words());
+
+        assert_eq!(Some(password), attack.password.as_deref());
+        assert!(attack.guesses > 0 && attack.guesses <= WordList::bundled().words().len());
+    }
+}
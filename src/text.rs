@@ -1,12 +1,384 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::fs;
 use std::fs::File;
-use std::io::{BufRead, BufReader};
-use rand::RngCore;
+use std::io::{self, BufRead, BufReader, ErrorKind, Read, Write};
+use std::path::Path;
+use std::sync::OnceLock;
+use lazy_static::lazy_static;
+use rand::Rng;
 use crate::fraction::Fraction;
 
 const MOST_FREQUENT_CHARACTERS: &str = "etaoin shrdlu";
 const WORDS_FILE: &str = "/usr/share/dict/words";
 
+// A few pages of public-domain (or original) English prose for training the smaller models
+const ENGLISH_CORPUS: &str = include_str!("corpus/english.txt");
+
+// Quadgram and letter frequency tables written by examples/language_tables.rs, each trained on
+// about 2MB of text in that language
+const ENGLISH_QUADGRAM_TABLE: &[u8] = include_bytes!("tables/english_quadgrams.bin");
+const GERMAN_QUADGRAM_TABLE: &[u8] = include_bytes!("tables/german_quadgrams.bin");
+const FRENCH_QUADGRAM_TABLE: &[u8] = include_bytes!("tables/french_quadgrams.bin");
+const SPANISH_QUADGRAM_TABLE: &[u8] = include_bytes!("tables/spanish_quadgrams.bin");
+const GERMAN_CHI_SQUARED_TABLE: &[u8] = include_bytes!("tables/german_chi_squared.bin");
+const FRENCH_CHI_SQUARED_TABLE: &[u8] = include_bytes!("tables/french_chi_squared.bin");
+const SPANISH_CHI_SQUARED_TABLE: &[u8] = include_bytes!("tables/spanish_chi_squared.bin");
+
+// About a thousand common English words, for systems without a word list
+const BUNDLED_WORDS: &str = include_str!("corpus/words.txt");
+
+// Relative frequencies of the letters a-z (from Lewand's "Cryptological Mathematics") and then
+// the space, which accounts for roughly one character in six in English prose
+const ENGLISH_FREQUENCIES: [f64; 27] = [
+    0.08167, 0.01492, 0.02782, 0.04253, 0.12702, 0.02228, 0.02015, 0.06094, 0.06966, 0.00153,
+    0.00772, 0.04025, 0.02406, 0.06749, 0.07507, 0.01929, 0.00095, 0.05987, 0.06327, 0.09056,
+    0.02758, 0.00978, 0.02360, 0.00150, 0.01974, 0.00074, 0.18000,
+];
+
+lazy_static! {
+    pub static ref ENGLISH_BIGRAMS: NgramModel = NgramModel::train(2, ENGLISH_CORPUS.as_bytes());
+    pub static ref ENGLISH_TRIGRAMS: NgramModel = NgramModel::train(3, ENGLISH_CORPUS.as_bytes());
+    pub static ref ENGLISH_QUADGRAMS: NgramModel = NgramModel::read_from(&mut &ENGLISH_QUADGRAM_TABLE[..]).unwrap();
+    pub static ref GERMAN_QUADGRAMS: NgramModel = NgramModel::read_from(&mut &GERMAN_QUADGRAM_TABLE[..]).unwrap();
+    pub static ref FRENCH_QUADGRAMS: NgramModel = NgramModel::read_from(&mut &FRENCH_QUADGRAM_TABLE[..]).unwrap();
+    pub static ref SPANISH_QUADGRAMS: NgramModel = NgramModel::read_from(&mut &SPANISH_QUADGRAM_TABLE[..]).unwrap();
+
+    static ref ENGLISH_CHI_SQUARED: ChiSquared = ChiSquared::english();
+    static ref GERMAN_CHI_SQUARED: ChiSquared = ChiSquared::read_from(&mut &GERMAN_CHI_SQUARED_TABLE[..]).unwrap();
+    static ref FRENCH_CHI_SQUARED: ChiSquared = ChiSquared::read_from(&mut &FRENCH_CHI_SQUARED_TABLE[..]).unwrap();
+    static ref SPANISH_CHI_SQUARED: ChiSquared = ChiSquared::read_from(&mut &SPANISH_CHI_SQUARED_TABLE[..]).unwrap();
+
+    static ref BUNDLED_WORD_LIST: WordList =
+        WordList::new(BUNDLED_WORDS.lines().map(String::from).collect());
+}
+
+// Loaded on first use, unless a list was set with WordList::set_system_path before then
+static SYSTEM_WORD_LIST: OnceLock<WordList> = OnceLock::new();
+
+/// The languages with bundled models.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub enum Language {
+    English,
+    German,
+    French,
+    Spanish,
+}
+
+impl Language {
+    pub const ALL: [Language; 4] = [Language::English, Language::German, Language::French, Language::Spanish];
+
+    pub fn quadgrams(self) -> &'static NgramModel {
+        match self {
+            Language::English => &ENGLISH_QUADGRAMS,
+            Language::German => &GERMAN_QUADGRAMS,
+            Language::French => &FRENCH_QUADGRAMS,
+            Language::Spanish => &SPANISH_QUADGRAMS,
+        }
+    }
+
+    pub fn chi_squared(self) -> &'static ChiSquared {
+        match self {
+            Language::English => &ENGLISH_CHI_SQUARED,
+            Language::German => &GERMAN_CHI_SQUARED,
+            Language::French => &FRENCH_CHI_SQUARED,
+            Language::Spanish => &SPANISH_CHI_SQUARED,
+        }
+    }
+
+    /// Returns a scorer suited to contiguous text in this language: a quadgram model plus a
+    /// penalty for bytes that don't belong in plaintext.
+    pub fn scorer(self) -> impl TextScorer {
+        (self.quadgrams(), PrintableAscii)
+    }
+
+    /// Returns the language whose quadgram model best explains the given text.
+    pub fn detect(text: &[u8]) -> Self {
+        Self::ALL.into_iter()
+            .max_by(|a, b| a.quadgrams().score(text).total_cmp(&b.quadgrams().score(text)))
+            .unwrap()
+    }
+}
+
+/// Scores how much a string of bytes looks like plaintext; higher scores are better. Scores are
+/// only meant to be compared between texts of the same length.
+pub trait TextScorer {
+    fn score(&self, text: &[u8]) -> f64;
+}
+
+impl<T: TextScorer + ?Sized> TextScorer for &T {
+    fn score(&self, text: &[u8]) -> f64 {
+        (**self).score(text)
+    }
+}
+
+/// Combines two scorers by adding their scores.
+impl<A: TextScorer, B: TextScorer> TextScorer for (A, B) {
+    fn score(&self, text: &[u8]) -> f64 {
+        self.0.score(text) + self.1.score(text)
+    }
+}
+
+/// Scores text by the fit of its letter and space frequencies to a language's (i.e. the negated
+/// chi-squared statistic). Letters are case-folded and everything else is ignored, so this works
+/// well for text that isn't contiguous (like a column of bytes that share a key byte) when paired
+/// with [PrintableAscii] to punish junk.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ChiSquared {
+    frequencies: [f64; 27],
+}
+
+impl ChiSquared {
+    /// Creates a scorer from the relative frequencies of the letters a-z followed by the space.
+    pub fn new(frequencies: [f64; 27]) -> Self {
+        let total: f64 = frequencies.iter().sum();
+
+        Self {
+            frequencies: frequencies.map(|frequency| frequency / total),
+        }
+    }
+
+    pub fn english() -> Self {
+        Self::new(ENGLISH_FREQUENCIES)
+    }
+
+    /// Creates a scorer from the letter and space frequencies of a corpus. Every symbol is counted
+    /// once more than it appears, so symbols missing from a small corpus aren't impossible.
+    pub fn train(corpus: &[u8]) -> Self {
+        let mut counts = [1.0; 27];
+
+        for &b in corpus {
+            if b.is_ascii_alphabetic() {
+                counts[(b.to_ascii_lowercase() - b'a') as usize] += 1.0;
+            } else if b == b' ' {
+                counts[26] += 1.0;
+            }
+        }
+
+        Self::new(counts)
+    }
+
+    pub fn train_from_file(path: impl AsRef<Path>) -> io::Result<Self> {
+        Ok(Self::train(&fs::read(path)?))
+    }
+
+    /// Writes the frequencies as 27 little-endian f64s.
+    pub fn write_to(&self, writer: &mut impl Write) -> io::Result<()> {
+        for frequency in self.frequencies {
+            writer.write_all(&frequency.to_le_bytes())?;
+        }
+
+        Ok(())
+    }
+
+    /// Reads frequencies written by [ChiSquared::write_to]. They were normalized when the scorer
+    /// was created, so they're used as they are (normalizing again could change them slightly).
+    pub fn read_from(reader: &mut impl Read) -> io::Result<Self> {
+        let mut frequencies = [0.0; 27];
+
+        for frequency in frequencies.iter_mut() {
+            *frequency = read_f64(reader)?;
+
+            if !(frequency.is_finite() && *frequency > 0.0) {
+                return Err(io::Error::new(ErrorKind::InvalidData, "Invalid frequency"));
+            }
+        }
+
+        Ok(Self { frequencies })
+    }
+}
+
+impl TextScorer for ChiSquared {
+    fn score(&self, text: &[u8]) -> f64 {
+        let mut counts = [0u64; 27];
+
+        for &b in text {
+            if b.is_ascii_alphabetic() {
+                counts[(b.to_ascii_lowercase() - b'a') as usize] += 1;
+            } else if b == b' ' {
+                counts[26] += 1;
+            }
+        }
+
+        let total: u64 = counts.iter().sum();
+
+        if total == 0 {
+            return f64::NEG_INFINITY;
+        }
+
+        let chi_squared: f64 = counts.iter()
+            .zip(self.frequencies.iter())
+            .map(|(&count, &frequency)| {
+                let expected = frequency * total as f64;
+                (count as f64 - expected).powi(2) / expected
+            })
+            .sum();
+
+        -chi_squared
+    }
+}
+
+/// Penalizes bytes that rarely show up in plaintext. Control characters and anything outside of
+/// ASCII cost the most, followed by unusual punctuation; letters and spaces are free.
+pub struct PrintableAscii;
+
+impl TextScorer for PrintableAscii {
+    fn score(&self, text: &[u8]) -> f64 {
+        text.iter()
+            .map(|&b| match b {
+                b'a'..=b'z' | b'A'..=b'Z' | b' ' => 0.0,
+                b'0'..=b'9' | b'.' | b',' | b'\'' | b'"' | b'!' | b'?' | b'-' | b';' | b':' | b'\n' => -1.0,
+                _ if b.is_ascii_graphic() || b.is_ascii_whitespace() => -5.0,
+                _ => -20.0,
+            })
+            .sum()
+    }
+}
+
+/// A log-likelihood n-gram language model: text scores the sum of the log-probabilities of its
+/// n-grams, and n-grams that never appeared in training get a fixed floor.
+///
+/// Letters are case-folded and all other printable ASCII is treated as a space both in training
+/// and in scoring, so the model only has to learn 27 symbols. Anything outside of printable ASCII
+/// passes through untouched, so it only matches n-grams that had the same bytes in training (like
+/// the UTF-8 encodings of accented letters).
+#[derive(Clone, Debug, PartialEq)]
+pub struct NgramModel {
+    n: usize,
+    log_probabilities: HashMap<Vec<u8>, f64>,
+    floor: f64,
+}
+
+impl NgramModel {
+    pub fn train(n: usize, corpus: &[u8]) -> Self {
+        assert!(n > 0);
+
+        let mut counts: HashMap<Vec<u8>, u64> = HashMap::new();
+
+        for ngram in normalize(corpus).windows(n) {
+            *counts.entry(ngram.to_vec()).or_insert(0) += 1;
+        }
+
+        let total = counts.values().sum::<u64>().max(1) as f64;
+
+        Self {
+            n,
+            log_probabilities: counts.into_iter()
+                .map(|(ngram, count)| (ngram, (count as f64 / total).log10()))
+                .collect(),
+            floor: (0.01 / total).log10(),
+        }
+    }
+
+    pub fn train_from_file(n: usize, path: impl AsRef<Path>) -> io::Result<Self> {
+        Ok(Self::train(n, &fs::read(path)?))
+    }
+
+    pub fn n(&self) -> usize {
+        self.n
+    }
+
+    /// Writes the model as n (one byte), the floor and the n-gram count, followed by each n-gram
+    /// and its log-probability. Numbers are little-endian and n-grams are sorted, so the same model
+    /// always serializes the same way.
+    pub fn write_to(&self, writer: &mut impl Write) -> io::Result<()> {
+        let n = u8::try_from(self.n).map_err(|_| io::Error::new(ErrorKind::InvalidInput, "n is too large"))?;
+
+        writer.write_all(&[n])?;
+        writer.write_all(&self.floor.to_le_bytes())?;
+        writer.write_all(&(self.log_probabilities.len() as u64).to_le_bytes())?;
+
+        let mut ngrams: Vec<_> = self.log_probabilities.iter().collect();
+        ngrams.sort_by_key(|(ngram, _)| *ngram);
+
+        for (ngram, log_probability) in ngrams {
+            writer.write_all(ngram)?;
+            writer.write_all(&log_probability.to_le_bytes())?;
+        }
+
+        Ok(())
+    }
+
+    /// Reads a model written by [NgramModel::write_to].
+    pub fn read_from(reader: &mut impl Read) -> io::Result<Self> {
+        let mut n = [0; 1];
+        reader.read_exact(&mut n)?;
+
+        let n = n[0] as usize;
+
+        if n == 0 {
+            return Err(io::Error::new(ErrorKind::InvalidData, "n must be positive"));
+        }
+
+        let floor = read_f64(reader)?;
+
+        let mut count = [0; 8];
+        reader.read_exact(&mut count)?;
+
+        let mut log_probabilities = HashMap::new();
+
+        for _ in 0..u64::from_le_bytes(count) {
+            let mut ngram = vec![0; n];
+            reader.read_exact(&mut ngram)?;
+
+            if log_probabilities.insert(ngram, read_f64(reader)?).is_some() {
+                return Err(io::Error::new(ErrorKind::InvalidData, "Duplicate n-gram"));
+            }
+        }
+
+        Ok(Self { n, log_probabilities, floor })
+    }
+}
+
+impl TextScorer for NgramModel {
+    fn score(&self, text: &[u8]) -> f64 {
+        normalize(text)
+            .windows(self.n)
+            .map(|ngram| self.log_probabilities.get(ngram).copied().unwrap_or(self.floor))
+            .sum()
+    }
+}
+
+/// Scores text in whichever bundled [Language] it fits best, for when the plaintext's language
+/// isn't known ahead of time. Use [Language::detect] to find out which one that was.
+pub struct AnyLanguage;
+
+impl TextScorer for AnyLanguage {
+    fn score(&self, text: &[u8]) -> f64 {
+        Language::ALL.into_iter()
+            .map(|language| language.scorer().score(text))
+            .fold(f64::NEG_INFINITY, f64::max)
+    }
+}
+
+fn read_f64(reader: &mut impl Read) -> io::Result<f64> {
+    let mut bytes = [0; 8];
+    reader.read_exact(&mut bytes)?;
+
+    Ok(f64::from_le_bytes(bytes))
+}
+
+fn normalize(text: &[u8]) -> Vec<u8> {
+    text.iter()
+        .map(|&b| {
+            if b.is_ascii_alphabetic() {
+                b.to_ascii_lowercase()
+            } else if b.is_ascii_graphic() || b.is_ascii_whitespace() {
+                b' '
+            } else {
+                b
+            }
+        })
+        .collect()
+}
+
+/// Returns [Language::English]'s scorer.
+pub fn english() -> impl TextScorer {
+    Language::English.scorer()
+}
+
+/// Scores a string by the fraction of its characters that are among the most common (lowercase)
+/// English characters. This is crude and easily fooled by capitals and punctuation; prefer a
+/// [TextScorer] like [english] or [ChiSquared].
 pub fn englishiness(string: &str) -> Fraction {
     let common_characters: HashSet<char> = MOST_FREQUENT_CHARACTERS
         .chars()
@@ -17,22 +389,95 @@ pub fn englishiness(string: &str) -> Fraction {
         .filter(|c| common_characters.contains(c))
         .count();
 
-    Fraction::new(common_character_count as u64, string.len() as u64)
+    Fraction::new(common_character_count as u64, string.len().max(1) as u64)
 }
 
+/// A list of words to pick random keys and passwords from.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WordList {
+    words: Vec<String>,
+}
+
+impl WordList {
+    pub fn new(words: Vec<String>) -> Self {
+        assert!(!words.is_empty(), "Word lists must not be empty");
+
+        Self { words }
+    }
+
+    /// Loads a word list with one word per line. Blank lines are skipped.
+    pub fn from_file(path: impl AsRef<Path>) -> io::Result<Self> {
+        let mut words = vec![];
+
+        for line in BufReader::new(File::open(path)?).lines() {
+            let line = line?;
+            let word = line.trim();
+
+            if !word.is_empty() {
+                words.push(word.to_string());
+            }
+        }
+
+        if words.is_empty() {
+            return Err(io::Error::new(ErrorKind::InvalidData, "No words in word list"));
+        }
+
+        Ok(Self::new(words))
+    }
+
+    /// Returns the list of common English words bundled with the crate, which is the same on
+    /// every machine.
+    pub fn bundled() -> &'static Self {
+        &BUNDLED_WORD_LIST
+    }
+
+    /// Returns the system word list (`/usr/share/dict/words` unless overridden with
+    /// [WordList::set_system_path]), falling back to the bundled list if it's missing. The list is
+    /// only loaded once.
+    pub fn system() -> &'static Self {
+        SYSTEM_WORD_LIST.get_or_init(|| Self::from_file(WORDS_FILE).unwrap_or_else(|_| BUNDLED_WORD_LIST.clone()))
+    }
+
+    /// Loads the word list at `path` and uses it as [WordList::system] (and so for [random_word])
+    /// from then on. Fails if the list can't be loaded, or if the system list was already loaded.
+    pub fn set_system_path(path: impl AsRef<Path>) -> io::Result<()> {
+        let words = Self::from_file(path)?;
+
+        SYSTEM_WORD_LIST.set(words)
+            .map_err(|_| io::Error::new(ErrorKind::AlreadyExists, "System word list is already loaded"))
+    }
+
+    pub fn words(&self) -> &[String] {
+        &self.words
+    }
+
+    pub fn random_word(&self, rng: &mut impl Rng) -> &str {
+        &self.words[rng.gen_range(0..self.words.len())]
+    }
+}
+
+/// Returns a random word from [WordList::system].
 pub fn random_word() -> String {
-    let line_count = BufReader::new(File::open(WORDS_FILE).unwrap()).lines().count();
+    random_word_with_rng(&mut rand::thread_rng())
+}
 
-    BufReader::new(File::open(WORDS_FILE).unwrap())
-        .lines()
-        .nth(rand::thread_rng().next_u32() as usize % line_count)
-        .unwrap()
-        .unwrap()
+/// Returns a random word from [WordList::system] chosen with the given generator. Only the
+/// bundled list is the same everywhere, so use [WordList::bundled] directly when results need to
+/// be reproducible across machines.
+pub fn random_word_with_rng(rng: &mut impl Rng) -> String {
+    WordList::system().random_word(rng).to_string()
 }
 
 #[cfg(test)]
 mod test {
-    use crate::text::englishiness;
+    use std::io::ErrorKind;
+    use rand::{Rng, SeedableRng};
+    use rand::rngs::StdRng;
+    use crate::text::{AnyLanguage, ChiSquared, english, englishiness, ENGLISH_BIGRAMS, ENGLISH_TRIGRAMS, Language, NgramModel, PrintableAscii, random_word, random_word_with_rng, TextScorer, WordList};
+    use crate::xor::{break_single_byte_xor, single_byte_xor};
+
+    // Sentences that don't appear in the training corpus (shared with the text scoring benchmark)
+    const HELD_OUT_TEXT: &str = include_str!("corpus/held_out_english.txt");
 
     #[test]
     fn test_englishiness() {
@@ -40,4 +485,179 @@ mod test {
             englishiness("jkdbfnjkvndkvlbmsidfnuvndfnvblskdnblkd")
         );
     }
+
+    #[test]
+    fn test_scorers() {
+        let english_text = b"This is a relatively normal string of English text";
+        let junk = b"jkdbfnjkvndkvlbmsidfnuvndfnvblskdnblkdjkdbfnjkvnd";
+
+        let scorers: [&dyn TextScorer; 5] = [
+            &ChiSquared::english(),
+            &*ENGLISH_BIGRAMS,
+            &*ENGLISH_TRIGRAMS,
+            &english(),
+            &(ChiSquared::english(), PrintableAscii),
+        ];
+
+        for scorer in scorers {
+            assert!(scorer.score(english_text) > scorer.score(junk));
+            assert!(scorer.score(english_text) > scorer.score(&single_byte_xor(english_text, 0x20)));
+        }
+
+        assert!(PrintableAscii.score(b"Hello there") > PrintableAscii.score(b"Hello\x00there"));
+    }
+
+    #[test]
+    fn test_train() {
+        let model = NgramModel::train(2, b"abab");
+
+        assert_eq!(2, model.n());
+        assert!(model.score(b"ab") > model.score(b"aa"));
+        assert_eq!(model.score(b"aa"), model.score(b"a\x01"));
+    }
+
+    #[test]
+    fn test_serialization() {
+        let model = NgramModel::train(3, HELD_OUT_TEXT.as_bytes());
+        let mut serialized = vec![];
+        model.write_to(&mut serialized).unwrap();
+
+        assert_eq!(model, NgramModel::read_from(&mut serialized.as_slice()).unwrap());
+        assert!(NgramModel::read_from(&mut &serialized[..serialized.len() - 1]).is_err());
+
+        serialized[0] = 0;
+        assert!(NgramModel::read_from(&mut serialized.as_slice()).is_err());
+
+        let chi_squared = ChiSquared::train(HELD_OUT_TEXT.as_bytes());
+        let mut serialized = vec![];
+        chi_squared.write_to(&mut serialized).unwrap();
+
+        assert_eq!(27 * 8, serialized.len());
+        assert_eq!(chi_squared, ChiSquared::read_from(&mut serialized.as_slice()).unwrap());
+        assert!(ChiSquared::read_from(&mut [0; 27 * 8].as_slice()).is_err());
+    }
+
+    #[test]
+    fn test_detect_language() {
+        // Sentences that don't appear in any of the training corpora
+        let samples = [
+            (Language::English, "We should probably leave before the rain gets any worse."),
+            (Language::German, "Wir sollten wahrscheinlich gehen, bevor der Regen noch stärker wird."),
+            (Language::French, "Nous devrions sans doute partir avant que la pluie ne devienne plus forte."),
+            (Language::Spanish, "Probablemente deberíamos irnos antes de que la lluvia empeore todavía más."),
+        ];
+
+        for (language, text) in samples {
+            assert_eq!(language, Language::detect(text.as_bytes()));
+
+            let ciphertext = single_byte_xor(text.as_bytes(), 0x37);
+            let (key, _) = break_single_byte_xor(&ciphertext, &AnyLanguage);
+
+            assert_eq!(0x37, key);
+            assert_eq!(language, Language::detect(&single_byte_xor(&ciphertext, key)));
+        }
+    }
+
+    #[test]
+    fn test_detect_held_out_text() {
+        const SAMPLE_LENGTH: usize = 40;
+
+        for (language, text) in [
+            (Language::English, HELD_OUT_TEXT),
+            (Language::German, include_str!("corpus/held_out_german.txt")),
+            (Language::French, include_str!("corpus/held_out_french.txt")),
+            (Language::Spanish, include_str!("corpus/held_out_spanish.txt")),
+        ] {
+            assert_eq!(language, Language::detect(text.as_bytes()));
+
+            let best_fit = Language::ALL.into_iter()
+                .max_by(|a, b| a.chi_squared().score(text.as_bytes()).total_cmp(&b.chi_squared().score(text.as_bytes())))
+                .unwrap();
+
+            assert_eq!(language, best_fit);
+
+            // Short samples are where a table trained on too little text falls back on its floor
+            let samples: Vec<&[u8]> = text.as_bytes().chunks_exact(SAMPLE_LENGTH).collect();
+            let detected = samples.iter().filter(|sample| Language::detect(sample) == language).count();
+
+            assert!(detected >= samples.len() * 19 / 20, "{:?}: {}/{}", language, detected, samples.len());
+        }
+    }
+
+    #[test]
+    fn test_key_recovery_accuracy() {
+        const SAMPLE_LENGTH: usize = 12;
+        const TRIALS: usize = 500;
+
+        let mut rng = StdRng::seed_from_u64(1);
+        let text = HELD_OUT_TEXT.as_bytes();
+
+        let mut legacy_successes = 0;
+        let mut quadgram_successes = 0;
+
+        for _ in 0..TRIALS {
+            let start = rng.gen_range(0..text.len() - SAMPLE_LENGTH);
+            let key = rng.gen();
+            let ciphertext = single_byte_xor(&text[start..start + SAMPLE_LENGTH], key);
+
+            let legacy_guess = (0..=u8::MAX)
+                .max_by_key(|&key| englishiness(&String::from_utf8_lossy(&single_byte_xor(&ciphertext, key))))
+                .unwrap();
+
+            if legacy_guess == key {
+                legacy_successes += 1;
+            }
+
+            if break_single_byte_xor(&ciphertext, &english()).0 == key {
+                quadgram_successes += 1;
+            }
+        }
+
+        assert!(quadgram_successes > legacy_successes);
+        assert!(quadgram_successes > TRIALS * 9 / 10);
+    }
+
+    #[test]
+    fn test_word_list() {
+        let words = WordList::bundled();
+
+        assert!(words.words().len() > 1_000);
+        assert!(words.words().iter().all(|word| !word.is_empty() && word.bytes().all(|b| b.is_ascii_lowercase())));
+
+        let a = words.random_word(&mut StdRng::seed_from_u64(29));
+        let b = words.random_word(&mut StdRng::seed_from_u64(29));
+
+        assert_eq!(a, b);
+        assert!(words.words().iter().any(|word| word == a));
+
+        let path = std::env::temp_dir().join(format!("cryptopals-words-{}.txt", std::process::id()));
+        std::fs::write(&path, "alpha\n\n  beta \n").unwrap();
+
+        assert_eq!(vec!["alpha", "beta"], WordList::from_file(&path).unwrap().words());
+
+        std::fs::write(&path, "\n").unwrap();
+        assert!(WordList::from_file(&path).is_err());
+
+        std::fs::remove_file(&path).unwrap();
+        assert!(WordList::from_file(&path).is_err());
+        assert!(WordList::set_system_path(&path).is_err());
+    }
+
+    #[test]
+    fn test_system_path() {
+        // Nothing else in the tests touches the system word list, so it hasn't been loaded yet
+        let path = std::env::temp_dir().join(format!("cryptopals-system-words-{}.txt", std::process::id()));
+        std::fs::write(&path, "gamma\ndelta\n").unwrap();
+
+        WordList::set_system_path(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(vec!["gamma", "delta"], WordList::system().words());
+        assert!(["gamma", "delta"].contains(&random_word().as_str()));
+        assert!(["gamma", "delta"].contains(&random_word_with_rng(&mut StdRng::seed_from_u64(29)).as_str()));
+
+        std::fs::write(&path, "epsilon\n").unwrap();
+        assert_eq!(ErrorKind::AlreadyExists, WordList::set_system_path(&path).unwrap_err().kind());
+        std::fs::remove_file(&path).unwrap();
+    }
 }
@@ -0,0 +1,28 @@
+use crate::text::TextScorer;
+
+pub fn single_byte_xor(bytes: &[u8], key: u8) -> Vec<u8> {
+    bytes.iter().map(|b| b ^ key).collect()
+}
+
+/// Returns the key byte under which the given ciphertext decrypts to the best-scoring plaintext,
+/// along with that plaintext's score.
+pub fn break_single_byte_xor(ciphertext: &[u8], scorer: &(impl TextScorer + ?Sized)) -> (u8, f64) {
+    (0..=u8::MAX)
+        .map(|key| (key, scorer.score(&single_byte_xor(ciphertext, key))))
+        .max_by(|(_, a), (_, b)| a.total_cmp(b))
+        .unwrap()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::text;
+    use crate::xor::{break_single_byte_xor, single_byte_xor};
+
+    #[test]
+    fn test_break_single_byte_xor() {
+        let plaintext = b"SHOUTING IN ALL CAPS, WITH PUNCTUATION!";
+        let ciphertext = single_byte_xor(plaintext, 0x5a);
+
+        assert_eq!(0x5a, break_single_byte_xor(&ciphertext, &text::english()).0);
+    }
+}